@@ -1,5 +1,5 @@
 use rackit::{load_config, create_example_config, Result};
-use rackit::config_engine::types::{StepType, ErrorAction};
+use rackit::config_engine::types::StepType;
 
 fn main() -> Result<()> {
     println!("🚀 Демонстрация универсального движка конфигурации");
@@ -34,11 +34,20 @@ fn main() -> Result<()> {
         // Показываем команды
         for (i, command) in device_config.command_sequence.iter().enumerate() {
             match &command.step_type {
-                StepType::Command { command: cmd, expected_prompt } => {
+                StepType::Command { command: cmd, expected_prompt, mode, parser, template } => {
                     println!("       {}. {} -> команда: '{}'", i+1, command.name, cmd);
                     if let Some(prompt) = expected_prompt {
                         println!("          ожидаемый промпт: '{}'", prompt);
                     }
+                    if let Some(mode) = mode {
+                        println!("          режим: {}", mode);
+                    }
+                    if let Some(parser) = parser {
+                        println!("          парсер: {}", parser);
+                    }
+                    if let Some(template) = template {
+                        println!("          шаблон TextFSM: {}", template);
+                    }
                 }
                 StepType::Login => {
                     println!("       {}. {} -> логин", i+1, command.name);
@@ -51,6 +51,14 @@ pub enum Commands {
         /// Продолжить выполнение даже при ошибках
         #[arg(long)]
         ignore_errors: bool,
+        
+        /// Записать сессии с устройствами в файл (для воспроизведения)
+        #[arg(long, value_name = "FILE")]
+        record: Option<PathBuf>,
+
+        /// Сохранить результаты (вывод и разобранные записи шагов) в JSON
+        #[arg(long, value_name = "FILE")]
+        json: Option<PathBuf>,
     },
     
     /// Валидировать конфигурацию (как terraform validate)
@@ -149,6 +157,17 @@ pub enum Commands {
         /// Команда для выполнения (если не указана - интерактивный режим)
         #[arg(short, long)]
         command: Option<String>,
+        
+        /// Записать сессию с устройством в файл (для воспроизведения)
+        #[arg(long, value_name = "FILE")]
+        record: Option<PathBuf>,
+    },
+    
+    /// Показать последовательные порты (USB адаптеры с VID/PID и серийным номером)
+    Ports {
+        /// Определить скорость консоли на каждом порту (в порт отправляется Enter)
+        #[arg(long)]
+        detect_baud: bool,
     },
 }
 
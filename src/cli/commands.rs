@@ -2,14 +2,16 @@ use crate::cli::args::{Commands, OutputFormat, ExampleTemplate};
 use crate::config_engine::{load_config, create_executor, create_example_config};
 use crate::config_engine::types::StepType;
 use crate::error::Result;
+use crate::transport::{available_ports, detect_baud_rate, SerialTransport, Transport, COMMON_BAUD_RATES};
+use crate::transport::discovery::AUTO_BAUD_PROBE;
 
 /// Выполняет CLI команду
 pub fn execute_command(command: Commands, verbose: u8, quiet: bool) -> Result<()> {
     match command {
         Commands::Run { 
-            config, device, scenario, dry_run, parallel, max_parallel, ignore_errors 
+            config, device, scenario, dry_run, parallel, max_parallel, ignore_errors, record, json
         } => {
-            cmd_run(config, device, scenario, dry_run, parallel, max_parallel, ignore_errors, verbose, quiet)
+            cmd_run(config, device, scenario, dry_run, parallel, max_parallel, ignore_errors, record, json, verbose, quiet)
         }
         Commands::Validate { config, strict } => {
             cmd_validate(config, strict, verbose, quiet)
@@ -26,13 +28,17 @@ pub fn execute_command(command: Commands, verbose: u8, quiet: bool) -> Result<()
         Commands::Check { config, device, ping_only, timeout } => {
             cmd_check(config, device, ping_only, timeout, verbose, quiet)
         }
-        Commands::Shell { config, device, command } => {
-            cmd_shell(config, device, command, verbose, quiet)
+        Commands::Shell { config, device, command, record } => {
+            cmd_shell(config, device, command, record, verbose, quiet)
+        }
+        Commands::Ports { detect_baud } => {
+            cmd_ports(detect_baud, verbose, quiet)
         }
     }
 }
 
 /// rackit run config.toml
+#[allow(clippy::too_many_arguments)]
 fn cmd_run(
     config_path: std::path::PathBuf,
     device_filter: Option<String>,
@@ -41,6 +47,8 @@ fn cmd_run(
     parallel: bool,
     _max_parallel: usize,
     ignore_errors: bool,
+    record: Option<std::path::PathBuf>,
+    json: Option<std::path::PathBuf>,
     verbose: u8,
     quiet: bool,
 ) -> Result<()> {
@@ -56,7 +64,14 @@ fn cmd_run(
     }
 
     // Загружаем конфигурацию
-    let executor = create_executor(&config_path)?;
+    let mut executor = create_executor(&config_path)?;
+    if let Some(record_path) = &record {
+        if !quiet {
+            println!("📼 Запись сессий в: {}", record_path.display());
+        }
+        executor.set_record_path(Some(record_path));
+    }
+    executor.validate()?;
     let config = executor.config();
 
     if verbose > 0 {
@@ -99,6 +114,7 @@ fn cmd_run(
     // Выполняем команды
     let mut success_count = 0;
     let mut error_count = 0;
+    let mut results = Vec::new();
 
     for device_id in devices_to_run {
         if verbose > 0 {
@@ -124,6 +140,7 @@ fn cmd_run(
                         }
                     }
                 }
+                results.push(result);
             }
             Err(e) => {
                 error_count += 1;
@@ -137,6 +154,13 @@ fn cmd_run(
         }
     }
 
+    if let Some(json_path) = &json {
+        std::fs::write(json_path, serde_json::to_string_pretty(&results)?)?;
+        if !quiet {
+            println!("💾 Результаты сохранены в: {}", json_path.display());
+        }
+    }
+
     if !quiet {
         println!();
         println!("📊 Итоги выполнения:");
@@ -172,8 +196,12 @@ fn cmd_validate(
     }
 
     // Загружаем и валидируем конфигурацию
-    match load_config(&config_path) {
-        Ok(config) => {
+    // Имена парсеров проверяются по реестру движка - до подключения к устройствам
+    let executor = create_executor(&config_path)
+        .and_then(|executor| executor.validate().map(|()| executor));
+    match executor {
+        Ok(executor) => {
+            let config = executor.config();
             if !quiet {
                 println!("✅ Синтаксис конфигурации корректен");
                 println!("   📱 Устройств: {}", config.devices.len());
@@ -252,7 +280,19 @@ fn cmd_plan(
                 let step_desc = match &step.step_type {
                     StepType::Login => "🔐 Вход в систему".to_string(),
                     StepType::Logout => "🚪 Выход из системы".to_string(),
-                    StepType::Command { command, .. } => format!("💻 Команда: '{}'", command),
+                    StepType::Command { command, mode, parser, template, .. } => {
+                        let mut desc = format!("💻 Команда: '{}'", command);
+                        if let Some(mode) = mode {
+                            desc.push_str(&format!(" (режим {})", mode));
+                        }
+                        if let Some(parser) = parser {
+                            desc.push_str(&format!(" → парсер {}", parser));
+                        }
+                        if let Some(template) = template {
+                            desc.push_str(&format!(" → шаблон {}", template));
+                        }
+                        desc
+                    }
                     StepType::Delay { milliseconds } => format!("⏱️ Пауза: {} мс", milliseconds),
                     StepType::WaitPrompt { prompt } => format!("⏳ Ожидание: '{}'", prompt),
                     StepType::CheckResponse { contains, .. } => format!("✅ Проверка: '{}'", contains),
@@ -391,12 +431,12 @@ fn cmd_example(
             std::fs::write(&output_path, eltex_config)?;
         }
         ExampleTemplate::Cisco => {
-            println!("⚠️ Cisco шаблон пока не реализован, создаем полный пример");
-            create_example_config(&output_path)?;
+            let cisco_config = create_cisco_example();
+            std::fs::write(&output_path, cisco_config)?;
         }
         ExampleTemplate::Linux => {
-            println!("⚠️ Linux шаблон пока не реализован, создаем полный пример");
-            create_example_config(&output_path)?;
+            let linux_config = create_linux_example();
+            std::fs::write(&output_path, linux_config)?;
         }
     }
 
@@ -458,9 +498,10 @@ fn cmd_check(
 
 /// rackit shell config.toml --device router1
 fn cmd_shell(
-    _config_path: std::path::PathBuf,
+    config_path: std::path::PathBuf,
     device_id: String,
     command: Option<String>,
+    record: Option<std::path::PathBuf>,
     _verbose: u8,
     quiet: bool,
 ) -> Result<()> {
@@ -470,13 +511,101 @@ fn cmd_shell(
         if let Some(cmd) = &command {
             println!("   Команда: {}", cmd);
         } else {
-            println!("   Режим: интерактивный");
+            println!("   Режим: интерактивный (выход - quit или Ctrl+D)");
+        }
+        if let Some(record_path) = &record {
+            println!("   Запись сессии: {}", record_path.display());
         }
         println!();
     }
 
-    println!("⚠️ Интерактивная оболочка пока не реализована");
-    
+    let mut executor = create_executor(&config_path)?;
+    executor.set_record_path(record.as_ref());
+    let mut device = executor.open_device(&device_id)?;
+
+    match command {
+        Some(cmd) => {
+            let output = device.execute_command(&cmd)?;
+            println!("{}", output);
+        }
+        None => {
+            let stdin = std::io::stdin();
+            let mut line = String::new();
+            loop {
+                print!("{}> ", device_id);
+                std::io::Write::flush(&mut std::io::stdout())?;
+
+                line.clear();
+                if stdin.read_line(&mut line)? == 0 {
+                    break;
+                }
+                let cmd = line.trim();
+                if cmd == "quit" {
+                    break;
+                }
+                if cmd.is_empty() {
+                    continue;
+                }
+
+                match device.execute_command(cmd) {
+                    Ok(output) => println!("{}", output),
+                    Err(e) => eprintln!("❌ {}", e),
+                }
+            }
+        }
+    }
+
+    device.disconnect()?;
+    Ok(())
+}
+
+/// rackit ports
+fn cmd_ports(detect_baud: bool, _verbose: u8, quiet: bool) -> Result<()> {
+    let ports = available_ports()?;
+
+    if ports.is_empty() {
+        if !quiet { println!("🔌 Последовательные порты не найдены"); }
+        return Ok(());
+    }
+
+    if !quiet { println!("🔌 Последовательные порты:"); }
+    println!("┌─────────────────────┬────────┬───────────┬─────────────┬─────────────────┬─────────────────────────┬─────────┐");
+    println!("│ Порт                │ Тип    │ VID:PID   │ Расположение│ Серийный номер  │ Устройство              │ Скорость│");
+    println!("├─────────────────────┼────────┼───────────┼─────────────┼─────────────────┼─────────────────────────┼─────────┤");
+
+    for port in &ports {
+        let (vid_pid, location, serial_number, product) = match &port.usb {
+            Some(usb) => (
+                format!("{:04x}:{:04x}", usb.vid, usb.pid),
+                usb.location.clone().unwrap_or_default(),
+                usb.serial_number.clone().unwrap_or_default(),
+                usb.product.clone().or_else(|| usb.manufacturer.clone()).unwrap_or_default(),
+            ),
+            None => (String::new(), String::new(), String::new(), String::new()),
+        };
+
+        let baud_rate = if detect_baud {
+            let mut transport = SerialTransport::new(port.name.clone(), COMMON_BAUD_RATES[0]);
+            let detected = transport.connect()
+                .and_then(|_| detect_baud_rate(&mut transport, COMMON_BAUD_RATES, AUTO_BAUD_PROBE));
+            let _ = transport.disconnect();
+            detected.map(|rate| rate.to_string()).unwrap_or_else(|_| "?".to_string())
+        } else {
+            "-".to_string()
+        };
+
+        println!("│ {:<19} │ {:<6} │ {:<9} │ {:<11} │ {:<15} │ {:<23} │ {:<7} │",
+            truncate(&port.name, 19),
+            port.kind(),
+            vid_pid,
+            truncate(&location, 11),
+            truncate(&serial_number, 15),
+            truncate(&product, 23),
+            baud_rate
+        );
+    }
+    println!("└─────────────────────┴────────┴───────────┴─────────────┴─────────────────┴─────────────────────────┴─────────┘");
+
     Ok(())
 }
 
@@ -571,4 +700,101 @@ name = "logout"
 step_type = { type = "logout" }
 on_error = "continue"
 "#.to_string()
-} 
\ No newline at end of file
+} 
+
+fn create_cisco_example() -> String {
+    r#"# Пример конфигурации для Cisco IOS / IOS-XE
+
+[global_settings]
+max_retries = 3
+command_delay_ms = 200
+default_timeout_seconds = 30
+
+[devices.cisco_switch]
+[devices.cisco_switch.device_info]
+name = "Cisco Catalyst 2960"
+device_type = "switch"
+model = "WS-C2960-24TT-L"
+vendor = "Cisco"
+
+[devices.cisco_switch.connection]
+transport = "serial"
+host = "/dev/ttyUSB0"
+baud_rate = 9600
+timeout_seconds = 10
+
+# Вход: Username/Password (если спросят), enable и terminal length 0
+[devices.cisco_switch.credentials]
+username = "admin"
+password = "password"
+enable_password = "enable"
+
+[[devices.cisco_switch.command_sequence]]
+name = "login"
+step_type = { type = "login" }
+on_error = "stop"
+
+[[devices.cisco_switch.command_sequence]]
+name = "show_version"
+step_type = { type = "command", data = { command = "show version" } }
+on_error = "continue"
+
+[[devices.cisco_switch.command_sequence]]
+name = "show_vlans"
+step_type = { type = "command", data = { command = "show vlan brief" } }
+on_error = "continue"
+
+[[devices.cisco_switch.command_sequence]]
+name = "logout"
+step_type = { type = "logout" }
+on_error = "continue"
+"#.to_string()
+}
+
+fn create_linux_example() -> String {
+    r#"# Пример конфигурации для Linux серверов
+
+[global_settings]
+max_retries = 3
+command_delay_ms = 0
+default_timeout_seconds = 30
+
+[devices.linux_server]
+[devices.linux_server.device_info]
+name = "Ubuntu Server"
+device_type = "server"
+model = "VM"
+vendor = "Linux"
+
+[devices.linux_server.connection]
+transport = "ssh"
+host = "192.168.1.10"
+port = 22
+timeout_seconds = 15
+
+[devices.linux_server.credentials]
+username = "ubuntu"
+password = "password"
+
+# Команда с ненулевым кодом завершения считается ошибкой шага
+[[devices.linux_server.command_sequence]]
+name = "login"
+step_type = { type = "login" }
+on_error = "stop"
+
+[[devices.linux_server.command_sequence]]
+name = "uptime"
+step_type = { type = "command", data = { command = "uptime" } }
+on_error = "continue"
+
+[[devices.linux_server.command_sequence]]
+name = "check_nginx"
+step_type = { type = "command", data = { command = "systemctl is-active nginx" } }
+on_error = "continue"
+
+[[devices.linux_server.command_sequence]]
+name = "logout"
+step_type = { type = "logout" }
+on_error = "continue"
+"#.to_string()
+}
@@ -0,0 +1,445 @@
+use crate::config_engine::executor::{serial_settings, ConfigExecutor, ExecutionResult};
+use crate::config_engine::types::{BaudRate, CommandStep, DeviceConfig, ErrorAction, StepType, TransportType};
+use crate::device::DeviceCommands;
+use crate::error::{Error, Result};
+use crate::transport::{AsyncTcpTransport, AsyncTelnetTransport, AsyncTransport, BlockOnTransport, Transport};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio::task::{JoinHandle, JoinSet};
+
+/// Асинхронный вариант выполнения (feature `async`). Шаги выполняет тот же драйвер из
+/// `DriverRegistry`, что и в блокирующем режиме (вход, отключение постраничного вывода,
+/// режимы CLI, ошибки в выводе), поэтому результаты совпадают. Драйверы синхронные: каждый
+/// работает в своей блокирующей задаче tokio, сетевые транспорты (telnet, tcp) - на tokio
+/// через `BlockOnTransport`. Паузы и таймауты шагов - таймеры tokio; шаг, прерванный
+/// по таймауту, драйвер доделывает в фоне, и следующий шаг ждет его завершения
+impl ConfigExecutor {
+    /// Выполняет команды для конкретного устройства
+    pub async fn execute_device_async(&self, device_id: &str) -> Result<ExecutionResult> {
+        let device_config = self.device_config(device_id)?;
+        self.validate_device(device_config)?;
+        let transport = self.create_async_transport(device_config)?;
+        self.execute_device_with_driver_task(device_id, transport).await
+    }
+
+    /// Выполняет команды для устройства через переданный асинхронный транспорт
+    pub async fn execute_device_with_async_transport(
+        &self,
+        device_id: &str,
+        mut transport: Box<dyn AsyncTransport>,
+    ) -> Result<ExecutionResult> {
+        let device_config = self.device_config(device_id)?;
+        self.validate_device(device_config)?;
+        transport.set_timeout(self.device_timeout(device_config))?;
+        let transport = BlockOnTransport::new(transport, tokio::runtime::Handle::current());
+        self.execute_device_with_driver_task(device_id, Box::new(transport)).await
+    }
+
+    /// Выполняет шаги драйвером из реестра, работающим в блокирующей задаче
+    async fn execute_device_with_driver_task(
+        &self,
+        device_id: &str,
+        transport: Box<dyn Transport + Send>,
+    ) -> Result<ExecutionResult> {
+        let device_config = self.device_config(device_id)?;
+
+        println!("🚀 Выполнение команд для устройства: {}", device_config.device_info.name);
+
+        let mut result = ExecutionResult::new(device_id.to_string());
+        let mut driver = DriverTask::connect(Arc::new(self.clone()), device_id, transport).await?;
+
+        let command_delay = Duration::from_millis(self.config().global_settings.command_delay_ms);
+        for step in &device_config.command_sequence {
+            let outcome = self.execute_step_async(&mut driver, step, device_id).await
+                .and_then(|output| self.parse_step_output(step, output));
+            match outcome {
+                Ok((output, parsed)) => {
+                    result.add_success(step.name.clone(), output, parsed);
+                    tokio::time::sleep(command_delay).await;
+                }
+                Err(e) => {
+                    result.add_error(step.name.clone(), e.to_string());
+
+                    match &step.on_error {
+                        ErrorAction::Stop => {
+                            println!("❌ Остановка выполнения из-за ошибки в шаге '{}'", step.name);
+                            break;
+                        }
+                        ErrorAction::Continue => {
+                            println!("⚠️ Продолжение выполнения несмотря на ошибку в шаге '{}'", step.name);
+                            continue;
+                        }
+                        ErrorAction::Retry => {
+                            println!("🔄 Повтор шага '{}' из-за ошибки", step.name);
+                            // TODO: Реализовать логику повтора
+                            continue;
+                        }
+                        ErrorAction::GotoStep(_target) => {
+                            println!("↗️ Переход к другому шагу из-за ошибки (не реализовано)");
+                            continue;
+                        }
+                    }
+                }
+            }
+        }
+
+        driver.disconnect().await;
+
+        Ok(result)
+    }
+
+    /// Выполняет команды для всех устройств одновременно
+    pub async fn execute_all_devices_async(&self) -> Result<Vec<ExecutionResult>> {
+        self.validate()?;
+        let executor = Arc::new(self.clone());
+        let mut tasks = JoinSet::new();
+
+        for device_id in self.config().devices.keys().cloned() {
+            let executor = Arc::clone(&executor);
+            tasks.spawn(async move {
+                match executor.execute_device_async(&device_id).await {
+                    Ok(result) => result,
+                    Err(e) => {
+                        let mut error_result = ExecutionResult::new(device_id);
+                        error_result.add_error("connection".to_string(), e.to_string());
+                        error_result
+                    }
+                }
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            results.push(joined.map_err(|e| Error::Connection(format!("Задача устройства не завершилась: {}", e)))?);
+        }
+        results.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+
+        Ok(results)
+    }
+
+    /// Выполняет один шаг; `timeout_seconds` шага ограничивает его целиком
+    async fn execute_step_async(&self, driver: &mut DriverTask, step: &CommandStep, device_id: &str) -> Result<String> {
+        let run = async {
+            match &step.step_type {
+                StepType::Delay { milliseconds } => {
+                    println!("  🔧 Выполнение шага: {}", step.name);
+                    println!("    ⏱️ Пауза {} мс", milliseconds);
+                    tokio::time::sleep(Duration::from_millis(*milliseconds)).await;
+                    Ok("Pause completed".to_string())
+                }
+                _ => {
+                    let step = step.clone();
+                    let device_id = device_id.to_string();
+                    driver.run(move |executor, device| {
+                        executor.execute_step(device, &step, executor.device_config(&device_id)?)
+                    }).await
+                }
+            }
+        };
+
+        match step.timeout_seconds {
+            Some(seconds) => tokio::time::timeout(Duration::from_secs(seconds), run)
+                .await
+                .map_err(|_| Error::Timeout(String::new()))?,
+            None => run.await,
+        }
+    }
+
+    /// Создает транспорт согласно конфигурации: сетевые - на tokio, остальные - блокирующие
+    fn create_async_transport(&self, device_config: &DeviceConfig) -> Result<Box<dyn Transport + Send>> {
+        let connection = &device_config.connection;
+        let mut transport: Box<dyn AsyncTransport> = match connection.transport {
+            TransportType::Telnet => {
+                let port = connection.port.unwrap_or(23);
+                Box::new(AsyncTelnetTransport::new(connection.host.clone(), port))
+            }
+            TransportType::Tcp => {
+                let port = connection.port.ok_or_else(|| Error::ConfigValidation(
+                    "Для TCP транспорта необходимо указать port".to_string()
+                ))?;
+
+                if connection.rfc2217 {
+                    let baud_rate = match connection.baud_rate {
+                        Some(BaudRate::Auto) => {
+                            return Err(Error::ConfigValidation(
+                                "baud_rate = \"auto\" поддерживается только для serial транспорта".to_string()
+                            ));
+                        }
+                        Some(BaudRate::Fixed(rate)) => rate,
+                        None => 115200,
+                    };
+                    let transport = AsyncTelnetTransport::new(connection.host.clone(), port)
+                        .with_com_port_baud_rate(baud_rate)
+                        .with_com_port_settings(serial_settings(connection)?);
+                    Box::new(transport)
+                } else {
+                    Box::new(AsyncTcpTransport::new(connection.host.clone(), port))
+                }
+            }
+            _ => return self.create_transport(device_config),
+        };
+        // Таймаут устройства - уже для подключения
+        transport.set_timeout(self.device_timeout(device_config))?;
+        Ok(Box::new(BlockOnTransport::new(transport, tokio::runtime::Handle::current())))
+    }
+}
+
+/// Операция над драйвером, выполняемая в его блокирующей задаче
+type DriverJob = Box<dyn FnOnce(&ConfigExecutor, &mut Box<dyn DeviceCommands>) -> Result<String> + Send>;
+
+/// Синхронный драйвер из реестра в отдельной блокирующей задаче: драйвер создается,
+/// выполняет операции по очереди и отключается в ней же
+struct DriverTask {
+    jobs: Option<mpsc::Sender<(DriverJob, oneshot::Sender<Result<String>>)>>,
+    task: JoinHandle<()>,
+}
+
+impl DriverTask {
+    /// Подключается к устройству (с записью сессии, если она включена) и создает драйвер
+    async fn connect(executor: Arc<ConfigExecutor>, device_id: &str, transport: Box<dyn Transport + Send>) -> Result<Self> {
+        let (jobs, queue) = mpsc::channel::<(DriverJob, oneshot::Sender<Result<String>>)>();
+        let (connected, ready) = oneshot::channel();
+        let device_id = device_id.to_string();
+
+        let task = tokio::task::spawn_blocking(move || {
+            let mut device = match executor.connect_device(&device_id, transport) {
+                Ok(device) => device,
+                Err(e) => {
+                    let _ = connected.send(Err(e));
+                    return;
+                }
+            };
+            let _ = connected.send(Ok(()));
+
+            for (job, reply) in queue {
+                let _ = reply.send(job(&executor, &mut device));
+            }
+
+            if let Err(e) = device.disconnect() {
+                println!("⚠️ Ошибка при отключении: {}", e);
+            }
+        });
+
+        match ready.await {
+            Ok(Ok(())) => Ok(Self { jobs: Some(jobs), task }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(driver_stopped(task.await.err())),
+        }
+    }
+
+    /// Выполняет операцию в задаче драйвера и ждет результат
+    async fn run<F>(&mut self, job: F) -> Result<String>
+    where
+        F: FnOnce(&ConfigExecutor, &mut Box<dyn DeviceCommands>) -> Result<String> + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.jobs.as_ref()
+            .and_then(|jobs| jobs.send((Box::new(job), reply)).ok())
+            .ok_or_else(|| driver_stopped(None))?;
+        result.await.map_err(|_| driver_stopped(None))?
+    }
+
+    /// Закрывает очередь операций и ждет отключения драйвера
+    async fn disconnect(mut self) {
+        self.jobs.take();
+        if let Err(e) = self.task.await {
+            println!("⚠️ Ошибка при отключении: {}", driver_stopped(Some(e)));
+        }
+    }
+}
+
+fn driver_stopped(error: Option<tokio::task::JoinError>) -> Error {
+    match error {
+        Some(e) => Error::Connection(format!("Задача драйвера не завершилась: {}", e)),
+        None => Error::Connection("Задача драйвера завершилась".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_engine::TomlReader;
+    use crate::config_engine::ConfigReader;
+    use crate::transport::{BlockingTransport, MockTransport};
+
+    const CONFIG: &str = r#"
+[global_settings]
+command_delay_ms = 0
+
+[devices.router]
+[devices.router.device_info]
+name = "Test ESR"
+device_type = "router"
+model = "ESR-200"
+vendor = "Eltex"
+
+[devices.router.connection]
+transport = "serial"
+host = "/dev/null"
+
+[devices.router.credentials]
+username = "admin"
+password = "password"
+
+[[devices.router.command_sequence]]
+name = "show_version"
+step_type = { type = "command", data = { command = "show version" } }
+on_error = "continue"
+
+[[devices.router.command_sequence]]
+name = "slow"
+step_type = { type = "delay", data = { milliseconds = 5000 } }
+timeout_seconds = 1
+on_error = "continue"
+"#;
+
+    #[tokio::test]
+    async fn test_execute_device_async() {
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(CONFIG).unwrap());
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("esr-200# ")
+            .expect_command("show version")
+            .respond("show version\r\nSW version: 1.4.0\r\nesr-200# ")
+            .expect_command("exit");
+        let handle = mock.handle();
+
+        let result = executor
+            .execute_device_with_async_transport("router", Box::new(BlockingTransport::new(mock)))
+            .await
+            .unwrap();
+
+        assert_eq!(result.successful_commands[0].output, "SW version: 1.4.0");
+        // Пауза прервана таймаутом шага
+        assert_eq!(result.failed_commands[0].command_name, "slow");
+        assert!(!handle.is_connected());
+        handle.verify().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_login_retries_rejected_password() {
+        let config = CONFIG.replace("command_delay_ms = 0", "command_delay_ms = 0\nmax_retries = 1")
+            .replace("name = \"show_version\"", "name = \"login\"\nstep_type = { type = \"login\" }\non_error = \"stop\"\n\n[[devices.router.command_sequence]]\nname = \"show_version\"");
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200 login: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\n% Login invalid\r\nesr-200 login: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\nesr-200# ")
+            .expect_command("show version")
+            .respond("show version\r\nSW version: 1.4.0\r\nesr-200# ")
+            .expect_command("exit");
+        let handle = mock.handle();
+
+        let result = executor
+            .execute_device_with_async_transport("router", Box::new(BlockingTransport::new(mock)))
+            .await
+            .unwrap();
+
+        assert_eq!(result.successful_commands[0].command_name, "login");
+        assert_eq!(result.successful_commands[1].output, "SW version: 1.4.0");
+        handle.verify().unwrap();
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200 login: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\n% Login invalid\r\nesr-200 login: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\n% Login invalid\r\nesr-200 login: ")
+            .expect_command("exit");
+        let result = executor
+            .execute_device_with_async_transport("router", Box::new(BlockingTransport::new(mock)))
+            .await
+            .unwrap();
+
+        assert_eq!(result.success_count(), 0);
+        assert_eq!(result.failed_commands[0].command_name, "login");
+        assert!(result.failed_commands[0].error_message.contains("% Login invalid"));
+    }
+
+    #[tokio::test]
+    async fn test_registry_driver_matches_blocking_executor() {
+        let head = CONFIG.split("\n[[devices.router.command_sequence]]").next().unwrap();
+        let config = head.replace("vendor = \"Eltex\"", "vendor = \"Cisco\"") + r#"
+[[devices.router.command_sequence]]
+name = "login"
+step_type = { type = "login" }
+on_error = "stop"
+
+[[devices.router.command_sequence]]
+name = "typo"
+step_type = { type = "command", data = { command = "shutdwn", mode = { sub_config = "interface Gi0/2" } } }
+on_error = "continue"
+
+[[devices.router.command_sequence]]
+name = "clock"
+step_type = { type = "command", data = { command = "show clock", mode = "privileged" } }
+on_error = "stop"
+"#;
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        // Вход, terminal length 0, режимы и `% Invalid input` - как у драйвера CiscoIos
+        let mock = || MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("Username: ")
+            .expect_command("admin")
+            .respond("Password: ")
+            .expect_command("password")
+            .respond("\r\nsw1#")
+            .expect_command("terminal length 0")
+            .respond("terminal length 0\r\nsw1#")
+            .expect_command("configure terminal")
+            .respond("configure terminal\r\nsw1(config)#")
+            .expect_command("interface Gi0/2")
+            .respond("interface Gi0/2\r\nsw1(config-if)#")
+            .expect_command("shutdwn")
+            .respond("shutdwn\r\n% Invalid input detected at '^' marker.\r\nsw1(config-if)#")
+            .expect_command("exit")
+            .respond("exit\r\nsw1(config)#")
+            .expect_command("end")
+            .respond("end\r\nsw1#")
+            .expect_command("show clock")
+            .respond("show clock\r\n*10:00:00.000 UTC Mon Jan 1 2024\r\nsw1#")
+            .expect_command("exit");
+
+        let async_mock = mock();
+        let handle = async_mock.handle();
+        let result = executor
+            .execute_device_with_async_transport("router", Box::new(BlockingTransport::new(async_mock)))
+            .await
+            .unwrap();
+        handle.verify().unwrap();
+
+        let blocking = tokio::task::spawn_blocking(move || executor.execute_device_with_transport("router", Box::new(mock())))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let succeeded = |result: &ExecutionResult| -> Vec<String> {
+            result.successful_commands.iter().map(|command| command.command_name.clone()).collect()
+        };
+        let failed = |result: &ExecutionResult| -> Vec<String> {
+            result.failed_commands.iter().map(|command| command.command_name.clone()).collect()
+        };
+        assert_eq!(succeeded(&result), ["login", "clock"]);
+        assert_eq!(failed(&result), ["typo"]);
+        assert!(result.failed_commands[0].error_message.contains("Invalid input"));
+        assert_eq!(succeeded(&result), succeeded(&blocking));
+        assert_eq!(failed(&result), failed(&blocking));
+        assert_eq!(result.successful_commands[1].output, blocking.successful_commands[1].output);
+    }
+}
@@ -1,6 +1,6 @@
 use std::path::Path;
 use crate::error::Result;
-use crate::config_engine::types::ConfigFile;
+use crate::config_engine::types::{ConfigFile, StepType};
 
 pub trait ConfigReader {
     /// Читает конфигурацию из файла
@@ -46,6 +46,14 @@ pub trait ConfigReader {
                     format!("Устройство '{}' должно содержать хотя бы одну команду", device_name)
                 ));
             }
+
+            for step in &device_config.command_sequence {
+                if let StepType::Command { parser: Some(_), template: Some(_), .. } = &step.step_type {
+                    return Err(crate::error::Error::ConfigValidation(
+                        format!("Шаг '{}' устройства '{}': parser и template нельзя указывать вместе", step.name, device_name)
+                    ));
+                }
+            }
         }
         
         Ok(())
@@ -1,41 +1,172 @@
-use crate::config_engine::types::{ConfigFile, DeviceConfig, StepType, ErrorAction, TransportType};
-use crate::transport::{Transport, SerialTransport};
-use crate::device::{DeviceCommands, EltexEsr200};
+use crate::config_engine::types::{
+    ConfigFile, ConnectionConfig, DeviceConfig, StepType, ErrorAction, TransportType, BaudRate, Parity, FlowControl,
+};
+use crate::transport::{
+    Transport, RecordingTransport, SerialSettings, SerialTransport, SshTransport, TcpTransport, TelnetTransport, COMMON_BAUD_RATES,
+};
+use crate::config_engine::types::CommandStep;
+use crate::device::{CliMode, DeviceCommands, DriverRegistry};
 use crate::error::{Error, Result};
+use crate::parser::{OutputParser, ParserRegistry, TextFsm};
+use serde::{Serialize, Serializer};
+#[cfg(unix)]
+use crate::transport::PtyTransport;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::thread;
 
 /// Исполнительный движок для выполнения команд из конфигурации
+#[derive(Clone)]
 pub struct ConfigExecutor {
     config: ConfigFile,
+    record_path: Option<PathBuf>,
+    drivers: DriverRegistry,
+    parsers: ParserRegistry,
+    config_dir: Option<PathBuf>,
+    /// Шаблоны TextFSM шагов, скомпилированные при первой проверке (по разрешенному пути)
+    templates: Arc<Mutex<BTreeMap<PathBuf, Arc<TextFsm>>>>,
 }
 
 impl ConfigExecutor {
     /// Создает новый экземпляр движка с загруженной конфигурацией
     pub fn new(config: ConfigFile) -> Self {
-        Self { config }
+        Self {
+            config,
+            record_path: None,
+            drivers: DriverRegistry::with_builtin_drivers(),
+            parsers: ParserRegistry::with_builtin_parsers(),
+            config_dir: None,
+            templates: Arc::default(),
+        }
+    }
+
+    /// Реестр драйверов, по которому выбирается драйвер устройства
+    /// (по умолчанию - встроенные драйверы)
+    pub fn set_driver_registry(&mut self, drivers: DriverRegistry) {
+        self.drivers = drivers;
+    }
+
+    /// Реестр драйверов устройств
+    pub fn drivers(&self) -> &DriverRegistry {
+        &self.drivers
+    }
+
+    /// Реестр парсеров, из которого шаги выбирают парсер вывода
+    /// (по умолчанию - встроенные парсеры)
+    pub fn set_parser_registry(&mut self, parsers: ParserRegistry) {
+        self.parsers = parsers;
+    }
+
+    /// Реестр парсеров вывода
+    pub fn parsers(&self) -> &ParserRegistry {
+        &self.parsers
+    }
+
+    /// Каталог, относительно которого разрешаются пути шаблонов (`template`) шагов;
+    /// `create_executor` задает каталог файла конфигурации. Без него пути берутся
+    /// относительно текущего каталога
+    pub fn set_config_dir<P: AsRef<Path>>(&mut self, dir: Option<P>) {
+        self.config_dir = dir.map(|dir| dir.as_ref().to_path_buf());
+        self.templates = Arc::default();
+    }
+
+    /// Записывать сессии с устройствами в файл (каждое устройство - отдельная сессия)
+    pub fn set_record_path<P: AsRef<Path>>(&mut self, path: Option<P>) {
+        self.record_path = path.map(|p| p.as_ref().to_path_buf());
+    }
+
+    /// Проверяет конфигурацию против реестров движка (имена парсеров, шаблоны TextFSM шагов)
+    /// без подключения к устройствам
+    pub fn validate(&self) -> Result<()> {
+        self.config.devices.values().try_for_each(|device_config| self.validate_device(device_config))
+    }
+
+    /// Проверяет шаги устройства: каждый `parser` должен быть зарегистрирован в `ParserRegistry`,
+    /// каждый `template` - читаться и компилироваться, оба вместе не допускаются
+    pub(crate) fn validate_device(&self, device_config: &DeviceConfig) -> Result<()> {
+        for step in &device_config.command_sequence {
+            match &step.step_type {
+                StepType::Command { parser: Some(_), template: Some(_), .. } => {
+                    return Err(Error::ConfigValidation(format!(
+                        "Шаг '{}' устройства '{}': parser и template нельзя указывать вместе",
+                        step.name, device_config.device_info.name
+                    )));
+                }
+                StepType::Command { parser: Some(parser), .. } if self.parsers.get(parser).is_none() => {
+                    return Err(Error::ConfigValidation(format!(
+                        "Шаг '{}' устройства '{}': неизвестный парсер {} (доступны: {})",
+                        step.name, device_config.device_info.name, parser, self.parsers.names().join(", ")
+                    )));
+                }
+                StepType::Command { template: Some(template), .. } => {
+                    self.template(template).map_err(|e| Error::ConfigValidation(format!(
+                        "Шаг '{}' устройства '{}': {}", step.name, device_config.device_info.name, e
+                    )))?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Шаблон TextFSM шага: путь разрешается относительно каталога конфигурации,
+    /// шаблон компилируется один раз
+    fn template(&self, template: &str) -> Result<Arc<TextFsm>> {
+        let path = match &self.config_dir {
+            Some(dir) => dir.join(template),
+            None => PathBuf::from(template),
+        };
+        let mut templates = self.templates.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(compiled) = templates.get(&path) {
+            return Ok(Arc::clone(compiled));
+        }
+        let compiled = Arc::new(TextFsm::from_file(&path).map_err(|e| match e {
+            Error::Io(e) => Error::ConfigValidation(format!("шаблон {}: {}", path.display(), e)),
+            e => e,
+        })?);
+        templates.insert(path, Arc::clone(&compiled));
+        Ok(compiled)
+    }
+
+    /// Подключается к устройству и возвращает его драйвер (для интерактивной работы)
+    pub fn open_device(&self, device_id: &str) -> Result<Box<dyn DeviceCommands>> {
+        let device_config = self.device_config(device_id)?;
+        let transport = self.create_transport(device_config)?;
+        self.connect_device(device_id, transport)
     }
 
     /// Выполняет команды для конкретного устройства
     pub fn execute_device(&self, device_id: &str) -> Result<ExecutionResult> {
-        let device_config = self.config.devices.get(device_id)
-            .ok_or_else(|| Error::ConfigValidation(format!("Устройство '{}' не найдено", device_id)))?;
+        let device_config = self.device_config(device_id)?;
+        
+        // Создаем транспорт (подключение выполняется в create_device)
+        let transport = self.create_transport(device_config)?;
+        
+        self.execute_device_with_transport(device_id, transport)
+    }
+
+    /// Выполняет команды для устройства через переданный транспорт
+    /// (например, MockTransport для проверки сценариев без оборудования)
+    pub fn execute_device_with_transport(&self, device_id: &str, transport: Box<dyn Transport>) -> Result<ExecutionResult> {
+        let device_config = self.device_config(device_id)?;
+        self.validate_device(device_config)?;
 
         println!("🚀 Выполнение команд для устройства: {}", device_config.device_info.name);
         
         let mut result = ExecutionResult::new(device_id.to_string());
         
-        // Создаем транспорт (заглушка, реальное подключение в create_device)
-        let transport = self.create_transport(device_config)?;
-        
         // Создаем устройство (здесь происходит реальное подключение)
-        let mut device = self.create_device(transport, device_config)?;
+        let mut device = self.connect_device(device_id, transport)?;
         
         // Выполняем последовательность команд
         for step in &device_config.command_sequence {
-            match self.execute_step(&mut device, step, device_config) {
-                Ok(output) => {
-                    result.add_success(step.name.clone(), output);
+            let outcome = self.execute_step(&mut device, step, device_config)
+                .and_then(|output| self.parse_step_output(step, output));
+            match outcome {
+                Ok((output, parsed)) => {
+                    result.add_success(step.name.clone(), output, parsed);
                     
                     // Пауза между командами
                     thread::sleep(Duration::from_millis(self.config.global_settings.command_delay_ms));
@@ -75,11 +206,26 @@ impl ConfigExecutor {
         Ok(result)
     }
 
+    /// Создает драйвер устройства, при необходимости включая запись сессии
+    pub(crate) fn connect_device(&self, device_id: &str, transport: Box<dyn Transport>) -> Result<Box<dyn DeviceCommands>> {
+        let device_config = self.device_config(device_id)?;
+        let transport: Box<dyn Transport> = match &self.record_path {
+            Some(path) => Box::new(RecordingTransport::new(transport, path, device_id)?),
+            None => transport,
+        };
+        self.create_device(transport, device_config)
+    }
+
+    pub(crate) fn device_config(&self, device_id: &str) -> Result<&DeviceConfig> {
+        self.config.devices.get(device_id)
+            .ok_or_else(|| Error::ConfigValidation(format!("Устройство '{}' не найдено", device_id)))
+    }
+
     /// Выполняет один шаг команды
-    fn execute_step(
+    pub(crate) fn execute_step(
         &self, 
         device: &mut Box<dyn DeviceCommands>, 
-        step: &crate::config_engine::types::CommandStep,
+        step: &CommandStep,
         device_config: &DeviceConfig
     ) -> Result<String> {
         println!("  🔧 Выполнение шага: {}", step.name);
@@ -91,11 +237,13 @@ impl ConfigExecutor {
             }
             StepType::Logout => {
                 // Выполняем выход
-                device.execute_command("exit")
+                device.logout()
             }
-            StepType::Command { command, expected_prompt: _ } => {
-                // Выполняем команду
-                device.execute_command(command)
+            StepType::Command { command, mode, .. } => {
+                match mode {
+                    Some(mode) => self.execute_in_mode(device.as_mut(), command, mode),
+                    None => device.execute_command(command),
+                }
             }
             StepType::Delay { milliseconds } => {
                 // Пауза
@@ -116,40 +264,126 @@ impl ConfigExecutor {
         }
     }
 
-    /// Выполняет вход в систему
+    /// Разбирает вывод шага парсером из поля `parser` или шаблоном TextFSM из `template`;
+    /// ошибка разбора - ошибка шага
+    pub(crate) fn parse_step_output(&self, step: &CommandStep, output: String) -> Result<(String, Option<serde_json::Value>)> {
+        let parsed = match &step.step_type {
+            StepType::Command { parser: Some(parser), .. } => {
+                println!("    🧩 Разбор вывода парсером {}", parser);
+                self.parsers.parse(parser, &output)?
+            }
+            StepType::Command { template: Some(template), .. } => {
+                println!("    🧩 Разбор вывода шаблоном {}", template);
+                self.template(template)?.parse(&output)?
+            }
+            _ => return Ok((output, None)),
+        };
+        Ok((output, Some(parsed)))
+    }
+
+    /// Выполняет команду в режиме `mode`; при ошибке возвращает CLI в режим до шага
+    fn execute_in_mode(&self, device: &mut dyn DeviceCommands, command: &str, mode: &CliMode) -> Result<String> {
+        let home = device.cli_mode();
+        println!("    🧭 Переход в режим {}", mode);
+
+        let result = device.navigate_to(mode).and_then(|_| device.execute_command(command));
+        if result.is_err() {
+            if let Some(home) = home {
+                if let Err(e) = device.navigate_to(&home) {
+                    println!("    ⚠️ Не удалось вернуться в режим {}: {}", home, e);
+                }
+            }
+        }
+        result
+    }
+
+    /// Выполняет вход в систему; отказ в аутентификации повторяется до `max_retries` раз
     fn execute_login(&self, device: &mut dyn DeviceCommands, device_config: &DeviceConfig) -> Result<String> {
-        // Для простоты пока используем базовую логику
-        // В реальности нужно будет адаптировать под разные типы устройств
-        
-        let username = &device_config.credentials.username;
-        let password = &device_config.credentials.password;
-        
-        println!("    🔐 Вход в систему как '{}'", username);
-        
-        // Отправляем имя пользователя
-        let _response1 = device.execute_command(username)?;
-        
-        // Отправляем пароль
-        let response2 = device.execute_command(password)?;
-        
-        Ok(response2)
+        let credentials = &device_config.credentials;
+        let retries = self.config.global_settings.max_retries;
+        println!("    🔐 Вход в систему как '{}'", credentials.username);
+
+        let mut attempt = 0;
+        loop {
+            match device.login(credentials) {
+                Err(Error::Authentication(message)) if attempt < retries => {
+                    attempt += 1;
+                    println!("    🔁 {}; повтор входа {} из {}", message, attempt, retries);
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Создает транспорт согласно конфигурации
-    fn create_transport(&self, device_config: &DeviceConfig) -> Result<Box<dyn Transport>> {
+    pub(crate) fn create_transport(&self, device_config: &DeviceConfig) -> Result<Box<dyn Transport + Send>> {
         match device_config.connection.transport {
             TransportType::Serial => {
-                let baud_rate = device_config.connection.baud_rate.unwrap_or(115200);
-                let transport = SerialTransport::new(device_config.connection.host.clone(), baud_rate);
+                let transport = serial_transport(&device_config.connection)?
+                    .with_timeout(self.device_timeout(device_config));
                 Ok(Box::new(transport))
             }
             TransportType::Telnet => {
-                // TODO: Реализовать Telnet транспорт
-                Err(Error::ConfigValidation("Telnet транспорт не реализован".to_string()))
+                let port = device_config.connection.port.unwrap_or(23);
+                let transport = TelnetTransport::new(device_config.connection.host.clone(), port)
+                    .with_timeout(self.device_timeout(device_config));
+                Ok(Box::new(transport))
+            }
+            TransportType::Tcp => {
+                let connection = &device_config.connection;
+                let port = connection.port.ok_or_else(|| Error::ConfigValidation(
+                    "Для TCP транспорта необходимо указать port".to_string()
+                ))?;
+                
+                if connection.rfc2217 {
+                    let baud_rate = match connection.baud_rate {
+                        Some(BaudRate::Auto) => {
+                            return Err(Error::ConfigValidation(
+                                "baud_rate = \"auto\" поддерживается только для serial транспорта".to_string()
+                            ));
+                        }
+                        Some(BaudRate::Fixed(rate)) => rate,
+                        None => 115200,
+                    };
+                    let transport = TelnetTransport::new(connection.host.clone(), port)
+                        .with_timeout(self.device_timeout(device_config))
+                        .with_com_port_baud_rate(baud_rate)
+                        .with_com_port_settings(serial_settings(connection)?);
+                    Ok(Box::new(transport))
+                } else {
+                    let transport = TcpTransport::new(connection.host.clone(), port)
+                        .with_timeout(self.device_timeout(device_config));
+                    Ok(Box::new(transport))
+                }
+            }
+            #[cfg(unix)]
+            TransportType::Pty => {
+                Ok(Box::new(PtyTransport::new(device_config.connection.host.clone())))
+            }
+            #[cfg(not(unix))]
+            TransportType::Pty => {
+                Err(Error::ConfigValidation("PTY транспорт доступен только на Unix".to_string()))
             }
             TransportType::Ssh => {
-                // TODO: Реализовать SSH транспорт
-                Err(Error::ConfigValidation("SSH транспорт не реализован".to_string()))
+                let connection = &device_config.connection;
+                let credentials = &device_config.credentials;
+                let timeout = self.device_timeout(device_config);
+                
+                let mut transport = SshTransport::new(
+                    connection.host.clone(),
+                    connection.port.unwrap_or(22),
+                    credentials.username.clone(),
+                )
+                .with_password(&credentials.password)
+                .with_timeout(timeout);
+                
+                if let Some(key_path) = &credentials.ssh_key_path {
+                    transport = transport.with_key_file(key_path);
+                }
+                if let Some(known_hosts) = &connection.known_hosts_path {
+                    transport = transport.with_known_hosts(known_hosts);
+                }
+                Ok(Box::new(transport))
             }
             TransportType::Http => {
                 // TODO: Реализовать HTTP транспорт
@@ -162,50 +396,30 @@ impl ConfigExecutor {
         }
     }
 
-    /// Создает устройство согласно конфигурации
-    fn create_device(&self, _transport: Box<dyn Transport>, device_config: &DeviceConfig) -> Result<Box<dyn DeviceCommands>> {
-        match device_config.device_info.vendor.as_str() {
-            "Eltex" => {
-                // Для Eltex создаем новый транспорт напрямую
-                if let TransportType::Serial = device_config.connection.transport {
-                    let baud_rate = device_config.connection.baud_rate.unwrap_or(115200);
-                    let mut serial_transport = SerialTransport::new(device_config.connection.host.clone(), baud_rate);
-                    
-                    // Подключаемся к транспорту
-                    serial_transport.connect()?;
-                    
-                    // Устанавливаем таймаут
-                    let timeout = Duration::from_secs(
-                        device_config.connection.timeout_seconds
-                            .unwrap_or(self.config.global_settings.default_timeout_seconds)
-                    );
-                    serial_transport.set_timeout(timeout)?;
-                    
-                    let eltex_device = EltexEsr200::new(serial_transport)?;
-                    Ok(Box::new(eltex_device))
-                } else {
-                    Err(Error::ConfigValidation("Eltex поддерживает только Serial транспорт".to_string()))
-                }
-            }
-            "Cisco" => {
-                // TODO: Реализовать поддержку Cisco
-                Err(Error::ConfigValidation("Cisco устройства не реализованы".to_string()))
-            }
-            "Ubuntu" => {
-                // TODO: Реализовать поддержку Linux серверов
-                Err(Error::ConfigValidation("Linux серверы не реализованы".to_string()))
-            }
-            vendor => {
-                Err(Error::ConfigValidation(format!("Неподдерживаемый производитель: {}", vendor)))
-            }
-        }
+    /// Таймаут устройства или глобальный таймаут по умолчанию
+    pub(crate) fn device_timeout(&self, device_config: &DeviceConfig) -> Duration {
+        Duration::from_secs(
+            device_config.connection.timeout_seconds
+                .unwrap_or(self.config.global_settings.default_timeout_seconds)
+        )
+    }
+
+    /// Подключает транспорт и создает драйвер устройства из реестра
+    fn create_device(&self, mut transport: Box<dyn Transport>, device_config: &DeviceConfig) -> Result<Box<dyn DeviceCommands>> {
+        // Драйвер ищем до подключения, чтобы не открывать порт впустую
+        let factory = self.drivers.resolve(device_config)?;
+
+        transport.connect()?;
+        transport.set_timeout(self.device_timeout(device_config))?;
+        factory(transport, device_config)
     }
 
     /// Выполняет команды для всех устройств
     pub fn execute_all_devices(&self) -> Result<Vec<ExecutionResult>> {
+        self.validate()?;
         let mut results = Vec::new();
         
-        for (device_id, _) in &self.config.devices {
+        for device_id in self.config.devices.keys() {
             match self.execute_device(device_id) {
                 Ok(result) => results.push(result),
                 Err(e) => {
@@ -225,30 +439,97 @@ impl ConfigExecutor {
     }
 }
 
-/// Результат выполнения команд для одного устройства
-#[derive(Debug, Clone)]
+/// Формат кадра линии из конфигурации: для serial и для удаленного порта RFC 2217
+pub(crate) fn serial_settings(connection: &ConnectionConfig) -> Result<SerialSettings> {
+    let data_bits = match connection.data_bits.unwrap_or(8) {
+        5 => serialport::DataBits::Five,
+        6 => serialport::DataBits::Six,
+        7 => serialport::DataBits::Seven,
+        8 => serialport::DataBits::Eight,
+        other => {
+            return Err(Error::ConfigValidation(format!("data_bits = {}: допустимо от 5 до 8", other)));
+        }
+    };
+    let stop_bits = match connection.stop_bits.unwrap_or(1) {
+        1 => serialport::StopBits::One,
+        2 => serialport::StopBits::Two,
+        other => {
+            return Err(Error::ConfigValidation(format!("stop_bits = {}: допустимо 1 или 2", other)));
+        }
+    };
+    let parity = match connection.parity.unwrap_or(Parity::None) {
+        Parity::None => serialport::Parity::None,
+        Parity::Odd => serialport::Parity::Odd,
+        Parity::Even => serialport::Parity::Even,
+    };
+    Ok(SerialSettings { data_bits, parity, stop_bits })
+}
+
+/// Последовательный порт с настройками линии из конфигурации
+fn serial_transport(connection: &ConnectionConfig) -> Result<SerialTransport> {
+    let flow_control = match connection.flow_control.unwrap_or(FlowControl::None) {
+        FlowControl::None => serialport::FlowControl::None,
+        FlowControl::Software => serialport::FlowControl::Software,
+        FlowControl::Hardware => serialport::FlowControl::Hardware,
+    };
+
+    let baud_rate = connection.baud_rate.unwrap_or(BaudRate::Fixed(115200));
+    let mut transport = SerialTransport::new(connection.host.clone(), baud_rate.fixed().unwrap_or(COMMON_BAUD_RATES[0]))
+        .with_serial_settings(serial_settings(connection)?)
+        .with_flow_control(flow_control);
+
+    if baud_rate == BaudRate::Auto {
+        transport = transport.with_auto_baud(COMMON_BAUD_RATES);
+    }
+    if let Some(level) = connection.dtr {
+        transport = transport.with_dtr(level);
+    }
+    if let Some(level) = connection.rts {
+        transport = transport.with_rts(level);
+    }
+    if let Some(ms) = connection.toggle_dtr_rts_ms {
+        transport = transport.with_dtr_rts_toggle(Duration::from_millis(ms));
+    }
+    if let Some(ms) = connection.send_break_ms {
+        transport = transport.with_break_on_connect(Duration::from_millis(ms));
+    }
+    Ok(transport)
+}
+
+/// Результат выполнения команд для одного устройства.
+/// В JSON (`to_json`) длительности записываются в миллисекундах
+#[derive(Debug, Clone, Serialize)]
 pub struct ExecutionResult {
     pub device_id: String,
     pub successful_commands: Vec<CommandResult>,
     pub failed_commands: Vec<CommandError>,
+    #[serde(rename = "execution_time_ms", serialize_with = "serialize_millis")]
     pub execution_time: std::time::Duration,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CommandResult {
     pub command_name: String,
     pub output: String,
+    /// Записи, разобранные парсером шага (`parser`), если он задан
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parsed: Option<serde_json::Value>,
+    #[serde(rename = "execution_time_ms", serialize_with = "serialize_millis")]
     pub execution_time: std::time::Duration,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CommandError {
     pub command_name: String,
     pub error_message: String,
 }
 
+fn serialize_millis<S: Serializer>(duration: &std::time::Duration, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
 impl ExecutionResult {
-    fn new(device_id: String) -> Self {
+    pub(crate) fn new(device_id: String) -> Self {
         Self {
             device_id,
             successful_commands: Vec::new(),
@@ -257,21 +538,34 @@ impl ExecutionResult {
         }
     }
 
-    fn add_success(&mut self, command_name: String, output: String) {
+    pub(crate) fn add_success(&mut self, command_name: String, output: String, parsed: Option<serde_json::Value>) {
         self.successful_commands.push(CommandResult {
             command_name,
             output,
+            parsed,
             execution_time: std::time::Duration::default(), // TODO: Измерять время
         });
     }
 
-    fn add_error(&mut self, command_name: String, error_message: String) {
+    pub(crate) fn add_error(&mut self, command_name: String, error_message: String) {
         self.failed_commands.push(CommandError {
             command_name,
             error_message,
         });
     }
 
+    /// Разобранные записи успешного шага `command_name`
+    pub fn parsed(&self, command_name: &str) -> Option<&serde_json::Value> {
+        self.successful_commands.iter()
+            .find(|command| command.command_name == command_name)
+            .and_then(|command| command.parsed.as_ref())
+    }
+
+    /// Результат в JSON: вывод и разобранные записи каждого шага
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
     /// Возвращает true если все команды выполнены успешно
     pub fn is_success(&self) -> bool {
         self.failed_commands.is_empty()
@@ -286,4 +580,481 @@ impl ExecutionResult {
     pub fn error_count(&self) -> usize {
         self.failed_commands.len()
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_engine::{ConfigReader, TomlReader};
+    use crate::transport::MockTransport;
+
+    const CONFIG: &str = r#"
+[global_settings]
+command_delay_ms = 0
+
+[devices.router]
+[devices.router.device_info]
+name = "Test ESR"
+device_type = "router"
+model = "ESR-200"
+vendor = "Eltex"
+
+[devices.router.connection]
+transport = "serial"
+host = "/dev/null"
+
+[devices.router.credentials]
+username = "admin"
+password = "password"
+
+[[devices.router.command_sequence]]
+name = "login"
+step_type = { type = "login" }
+on_error = "stop"
+
+[[devices.router.command_sequence]]
+name = "show_version"
+step_type = { type = "command", data = { command = "show version" } }
+on_error = "continue"
+
+[[devices.router.command_sequence]]
+name = "logout"
+step_type = { type = "logout" }
+on_error = "continue"
+"#;
+
+    #[test]
+    fn test_execute_device_with_mock_transport() {
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(CONFIG).unwrap());
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200 login: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\n********************************************\r\n*  Authorized access only                  *\r\n********************************************\r\nesr-200# ")
+            .expect_command("show version")
+            .respond("show version\r\nSW version: 1.4.0\r\nesr-200# ")
+            .expect_command("exit")
+            .expect_command("exit");
+        let handle = mock.handle();
+
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(result.success_count(), 3);
+        assert!(result.successful_commands[1].output.contains("SW version: 1.4.0"));
+        assert!(!handle.is_connected());
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_command_steps_navigate_cli_modes() {
+        let head = CONFIG.split("\n[[devices.router.command_sequence]]").next().unwrap();
+        let config = head.replace("vendor = \"Eltex\"", "vendor = \"Cisco\"") + r#"
+[[devices.router.command_sequence]]
+name = "login"
+step_type = { type = "login" }
+on_error = "stop"
+
+[[devices.router.command_sequence]]
+name = "uplink"
+step_type = { type = "command", data = { command = "description uplink", mode = { sub_config = "interface Gi0/1" } } }
+on_error = "stop"
+
+[[devices.router.command_sequence]]
+name = "typo"
+step_type = { type = "command", data = { command = "shutdwn", mode = { sub_config = "interface Gi0/2" } } }
+on_error = "continue"
+
+[[devices.router.command_sequence]]
+name = "clock"
+step_type = { type = "command", data = { command = "show clock", mode = "privileged" } }
+on_error = "stop"
+"#;
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("Username: ")
+            .expect_command("admin")
+            .respond("Password: ")
+            .expect_command("password")
+            .respond("\r\nsw1#")
+            .expect_command("terminal length 0")
+            .respond("terminal length 0\r\nsw1#")
+            .expect_command("configure terminal")
+            .respond("configure terminal\r\nsw1(config)#")
+            .expect_command("interface Gi0/1")
+            .respond("interface Gi0/1\r\nsw1(config-if)#")
+            .expect_command("description uplink")
+            .respond("description uplink\r\nsw1(config-if)#")
+            .expect_command("exit")
+            .respond("exit\r\nsw1(config)#")
+            .expect_command("interface Gi0/2")
+            .respond("interface Gi0/2\r\nsw1(config-if)#")
+            .expect_command("shutdwn")
+            .respond("shutdwn\r\n% Invalid input detected at '^' marker.\r\nsw1(config-if)#")
+            .expect_command("exit")
+            .respond("exit\r\nsw1(config)#")
+            .expect_command("interface Gi0/1")
+            .respond("interface Gi0/1\r\nsw1(config-if)#")
+            .expect_command("exit")
+            .respond("exit\r\nsw1(config)#")
+            .expect_command("end")
+            .respond("end\r\nsw1#")
+            .expect_command("show clock")
+            .respond("show clock\r\n*10:00:00.000 UTC Mon Jan 1 2024\r\nsw1#")
+            .expect_command("exit");
+        let handle = mock.handle();
+
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+
+        assert_eq!(result.success_count(), 3);
+        assert_eq!(result.error_count(), 1);
+        assert!(result.successful_commands[2].output.contains("UTC"));
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_generic_cli_modes_from_config() {
+        let head = CONFIG.split("\n[[devices.router.command_sequence]]").next().unwrap();
+        let config = head.replace("password = \"password\"", "password = \"password\"\nenable_password = \"secret\"") + r#"
+[devices.router.device_settings.prompts]
+main = 'sw1(\(config[^)]*\))?[>#]'
+
+[devices.router.device_settings.extra_params]
+driver = "generic"
+mode_privileged = "enable"
+mode_config = "configure terminal"
+
+[[devices.router.command_sequence]]
+name = "login"
+step_type = { type = "login" }
+on_error = "stop"
+
+[[devices.router.command_sequence]]
+name = "hostname"
+step_type = { type = "command", data = { command = "hostname sw1", mode = "config" } }
+on_error = "stop"
+
+[[devices.router.command_sequence]]
+name = "clock"
+step_type = { type = "command", data = { command = "show clock", mode = "privileged" } }
+on_error = "stop"
+"#;
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("Username: ")
+            .expect_command("admin")
+            .respond("Password: ")
+            .expect_command("password")
+            .respond("\r\nsw1>")
+            .expect_command("enable")
+            .respond("enable\r\nPassword: ")
+            .expect_command("secret")
+            .respond("\r\nsw1#")
+            .expect_command("configure terminal")
+            .respond("configure terminal\r\nsw1(config)#")
+            .expect_command("hostname sw1")
+            .respond("hostname sw1\r\nsw1(config)#")
+            .expect_command("exit")
+            .respond("exit\r\nsw1#")
+            .expect_command("show clock")
+            .respond("show clock\r\n10:00:00 UTC\r\nsw1#")
+            .expect_command("exit");
+        let handle = mock.handle();
+
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+
+        assert!(result.is_success(), "{:?}", result.failed_commands);
+        assert!(result.successful_commands[2].output.contains("UTC"));
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_parsed_output_in_result_and_json() {
+        let config = CONFIG
+            .replace(
+                r#"{ command = "show version" } }
+on_error = "continue""#,
+                r#"{ command = "show system", parser = "show_system" } }
+on_error = "continue"
+
+[[devices.router.command_sequence]]
+name = "users"
+step_type = { type = "command", data = { command = "show users", parser = "show_system" } }
+on_error = "continue""#,
+            )
+            .replace("name = \"show_version\"", "name = \"system\"");
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200# ")
+            .expect_command("show system")
+            .respond("show system\r\nSystem type:      Eltex Service Router ESR-200\r\nSystem name:      esr-200\r\nesr-200# ")
+            .expect_command("show users")
+            .respond("show users\r\nNo users\r\nesr-200# ")
+            .expect_command("exit")
+            .expect_command("exit");
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+
+        assert_eq!(result.parsed("system").unwrap()["system_type"], "Eltex Service Router ESR-200");
+        assert_eq!(result.parsed("login"), None);
+        assert_eq!(result.failed_commands[0].command_name, "users");
+        assert!(result.failed_commands[0].error_message.contains("Ошибка разбора"));
+
+        let json: serde_json::Value = serde_json::from_str(&result.to_json().unwrap()).unwrap();
+        assert_eq!(json["device_id"], "router");
+        assert_eq!(json["successful_commands"][1]["parsed"]["name"], "esr-200");
+        assert!(json["successful_commands"][1]["output"].as_str().unwrap().contains("System name"));
+        assert!(json["successful_commands"][0].get("parsed").is_none());
+    }
+
+    #[test]
+    fn test_unknown_parser_rejected_before_connect() {
+        let config = CONFIG.replace(r#"{ command = "show version" }"#, r#"{ command = "show version", parser = "esr_version" }"#);
+        let mut executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        let error = executor.validate().unwrap_err();
+        assert!(matches!(&error, Error::ConfigValidation(message) if message.contains("esr_version") && message.contains("show_system")));
+
+        // Драйвер не должен ничего отправить в порт
+        let mock = MockTransport::new();
+        let handle = mock.handle();
+        assert!(matches!(
+            executor.execute_device_with_transport("router", Box::new(mock)),
+            Err(Error::ConfigValidation(_))
+        ));
+        handle.verify().unwrap();
+
+        let mut parsers = ParserRegistry::with_builtin_parsers();
+        parsers.register("esr_version", crate::parser::parse_key_value);
+        executor.set_parser_registry(parsers);
+        executor.validate().unwrap();
+    }
+
+    #[test]
+    fn test_parser_with_template_rejected_before_connect() {
+        // Без проверки `TomlReader`: конфигурация может прийти и не из файла
+        let config = CONFIG.replace(
+            r#"{ command = "show version" }"#,
+            r#"{ command = "show version", parser = "show_system", template = "show_version.textfsm" }"#,
+        );
+        let executor = ConfigExecutor::new(toml::from_str(&config).unwrap());
+
+        let error = executor.validate().unwrap_err();
+        assert!(matches!(&error, Error::ConfigValidation(message) if message.contains("parser и template")), "{}", error);
+
+        let mock = MockTransport::new();
+        let handle = mock.handle();
+        assert!(matches!(
+            executor.execute_device_with_transport("router", Box::new(mock)),
+            Err(Error::ConfigValidation(_))
+        ));
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_textfsm_template_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("esr_show_users.textfsm");
+        std::fs::write(&template, "Value USER (\\S+)\nValue LINE (console|vty\\d+)\n\nStart\n  ^${USER}\\s+${LINE}\\s*$$ -> Record\n").unwrap();
+
+        // Путь шаблона - относительно каталога конфигурации, а не текущего каталога
+        let config = CONFIG
+            .replace(r#"{ command = "show version" } }"#, r#"{ command = "show users", template = "esr_show_users.textfsm" } }"#)
+            .replace("name = \"show_version\"", "name = \"users\"");
+        let mut executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+        executor.set_config_dir(Some(dir.path()));
+        executor.validate().unwrap();
+        // Шаблон скомпилирован при проверке и больше не читается с диска
+        std::fs::remove_file(&template).unwrap();
+
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200# ")
+            .expect_command("show users")
+            .respond("show users\r\nUser     Line\r\nadmin    console\r\noper     vty0\r\nesr-200# ")
+            .expect_command("exit")
+            .expect_command("exit");
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+
+        assert!(result.is_success(), "{:?}", result.failed_commands);
+        assert_eq!(
+            result.parsed("users").unwrap(),
+            &serde_json::json!([{ "USER": "admin", "LINE": "console" }, { "USER": "oper", "LINE": "vty0" }])
+        );
+    }
+
+    #[test]
+    fn test_broken_template_rejected_before_connect() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("broken.textfsm"), "Value USER (\\S+)\n\nStart\n  ^${USER} -> Missing\n").unwrap();
+
+        for template in ["broken.textfsm", "missing.textfsm"] {
+            let config = CONFIG.replace(
+                r#"{ command = "show version" }"#,
+                &format!(r#"{{ command = "show users", template = "{}" }}"#, template),
+            );
+            let mut executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+            executor.set_config_dir(Some(dir.path()));
+
+            let error = executor.validate().unwrap_err();
+            assert!(matches!(&error, Error::ConfigValidation(message) if message.contains("show_version") && message.contains(template)), "{}", error);
+
+            let mock = MockTransport::new();
+            let handle = mock.handle();
+            assert!(matches!(
+                executor.execute_device_with_transport("router", Box::new(mock)),
+                Err(Error::ConfigValidation(_))
+            ));
+            handle.verify().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_login_retries_rejected_credentials() {
+        let config = CONFIG.replace("command_delay_ms = 0", "command_delay_ms = 0\nmax_retries = 1");
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        let rejected = || MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200 login: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\nLogin incorrect\r\nesr-200 login: ");
+
+        let mock = rejected()
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\nesr-200# ")
+            .expect_command("show version")
+            .respond("show version\r\nSW version: 1.4.0\r\nesr-200# ")
+            .expect_command("exit")
+            .expect_command("exit");
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+        assert!(result.is_success());
+
+        let mock = rejected()
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("password")
+            .respond("\r\nLogin incorrect\r\nesr-200 login: ");
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+        assert_eq!(result.success_count(), 0);
+        assert!(result.failed_commands[0].error_message.contains("Login incorrect"));
+    }
+
+    #[test]
+    fn test_custom_driver_from_registry() {
+        struct Echo(Box<dyn Transport>);
+
+        impl DeviceCommands for Echo {
+            fn execute_command(&mut self, command: &str) -> Result<String> {
+                self.0.send_string(command)?;
+                Ok(command.to_uppercase())
+            }
+
+            fn disconnect(&mut self) -> Result<()> {
+                self.0.disconnect()
+            }
+
+            fn logout(&mut self) -> Result<String> {
+                Ok(String::new())
+            }
+        }
+
+        let config = CONFIG.replace("vendor = \"Eltex\"", "vendor = \"Acme\"");
+        let mut executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+        assert!(matches!(
+            executor.execute_device_with_transport("router", Box::new(MockTransport::new())),
+            Err(Error::ConfigValidation(_))
+        ));
+
+        let mut drivers = DriverRegistry::with_builtin_drivers();
+        drivers.register("Acme", None, |transport, _| Ok(Box::new(Echo(transport))));
+        executor.set_driver_registry(drivers);
+
+        let mock = MockTransport::new()
+            .expect_command("admin")
+            .expect_command("password")
+            .expect_command("show version");
+        let handle = mock.handle();
+        let result = executor.execute_device_with_transport("router", Box::new(mock)).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(result.successful_commands[1].output, "SHOW VERSION");
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_serial_line_settings() {
+        let config = CONFIG.replace(
+            "host = \"/dev/null\"",
+            "host = \"/dev/null\"\ndata_bits = 7\nparity = \"even\"\nstop_bits = 1\nflow_control = \"hardware\"\nsend_break_ms = 250",
+        );
+        let config = TomlReader::read_from_str(&config).unwrap();
+        let connection = &config.devices["router"].connection;
+        assert_eq!(connection.parity, Some(Parity::Even));
+        assert_eq!(connection.flow_control, Some(FlowControl::Hardware));
+        assert!(serial_transport(connection).is_ok());
+
+        let mut invalid = connection.clone();
+        invalid.data_bits = Some(9);
+        assert!(matches!(serial_transport(&invalid), Err(Error::ConfigValidation(_))));
+    }
+
+    #[test]
+    fn test_network_transports_connect_with_device_timeout() {
+        let connections = [
+            "transport = \"telnet\"\nhost = \"192.0.2.1\"",
+            "transport = \"tcp\"\nhost = \"192.0.2.1\"\nport = 2001",
+            "transport = \"tcp\"\nhost = \"192.0.2.1\"\nport = 2001\nrfc2217 = true",
+        ];
+        for connection in connections {
+            let config = CONFIG.replace(
+                "transport = \"serial\"\nhost = \"/dev/null\"",
+                &format!("{}\ntimeout_seconds = 3", connection),
+            );
+            let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+            let device_config = executor.device_config("router").unwrap();
+            assert!(!matches!(device_config.connection.transport, TransportType::Serial));
+
+            // Таймаут задан до connect(), а не после подключения в create_device
+            let transport = executor.create_transport(device_config).unwrap();
+            assert_eq!(transport.timeout(), Duration::from_secs(3), "{}", connection);
+        }
+    }
+
+    #[test]
+    fn test_auto_baud_rate_config() {
+        let config = CONFIG.replace("host = \"/dev/null\"", "host = \"/dev/null\"\nbaud_rate = \"auto\"");
+        let config = TomlReader::read_from_str(&config).unwrap();
+        assert_eq!(config.devices["router"].connection.baud_rate, Some(BaudRate::Auto));
+
+        let config = CONFIG.replace("host = \"/dev/null\"", "host = \"/dev/null\"\nbaud_rate = \"fast\"");
+        assert!(TomlReader::read_from_str(&config).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_device_against_simulated_cli() {
+        let script = r#"while IFS= read -r line; do case "$line" in '') ;; *) echo "ok: $line";; esac; printf 'esr-200# '; done"#;
+        let config = CONFIG
+            .replace("transport = \"serial\"", "transport = \"pty\"")
+            .replace("host = \"/dev/null\"", &format!("host = '''{}'''", script));
+        let executor = ConfigExecutor::new(TomlReader::read_from_str(&config).unwrap());
+
+        let result = executor.execute_device("router").unwrap();
+
+        assert!(result.is_success());
+        assert!(result.successful_commands[1].output.contains("ok: show version"));
+    }
+}
@@ -2,6 +2,8 @@ pub mod config_reader;
 pub mod types;
 pub mod toml_reader;
 pub mod executor;
+#[cfg(feature = "async")]
+mod async_executor;
 
 pub use config_reader::ConfigReader;
 pub use types::*;
@@ -24,9 +26,12 @@ pub fn load_config<P: AsRef<Path>>(path: P) -> Result<ConfigFile> {
 }
 
 /// Создает движок выполнения из файла конфигурации
+/// (пути шаблонов шагов разрешаются относительно каталога файла)
 pub fn create_executor<P: AsRef<Path>>(config_path: P) -> Result<ConfigExecutor> {
-    let config = load_config(config_path)?;
-    Ok(ConfigExecutor::new(config))
+    let config_path = config_path.as_ref();
+    let mut executor = ConfigExecutor::new(load_config(config_path)?);
+    executor.set_config_dir(config_path.parent());
+    Ok(executor)
 }
 
 /// Создает пример конфигурационного файла (только для демонстрации)
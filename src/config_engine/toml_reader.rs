@@ -18,7 +18,7 @@ impl ConfigReader for TomlReader {
         
         // Читаем содержимое файла
         let content = fs::read_to_string(path)
-            .map_err(|e| Error::Io(e))?;
+            .map_err(Error::Io)?;
         
         // Парсим TOML
         Self::read_from_str(&content)
@@ -151,13 +151,6 @@ timeout_seconds = 10
 retry_count = 3
 on_error = "stop"
 
-[[devices.cisco_switch.command_sequence]]
-name = "enable_mode"
-step_type = { type = "command", data = { command = "enable", expected_prompt = "#" } }
-timeout_seconds = 5
-retry_count = 2
-on_error = "stop"
-
 [[devices.cisco_switch.command_sequence]]
 name = "show_version"
 step_type = { type = "command", data = { command = "show version", expected_prompt = "#" } }
@@ -245,7 +238,7 @@ on_error = "continue"
 
 [devices.linux_server.device_settings]
 [devices.linux_server.device_settings.prompts]
-main = "$"
+main = '\$'
 root = "#"
 
 # ================================
@@ -289,7 +282,7 @@ on_error = "continue"
             .map_err(|e| Error::ConfigParse(format!("Failed to serialize TOML: {}", e)))?;
         
         fs::write(path, toml_string)
-            .map_err(|e| Error::Io(e))?;
+            .map_err(Error::Io)?;
         
         Ok(())
     }
@@ -332,4 +325,17 @@ mod tests {
         let config = TomlReader::read_from_file(temp_file.path()).unwrap();
         assert_eq!(config.devices.len(), 3);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parser_with_template_rejected() {
+        let toml_content = TomlReader::create_example_config().replacen(
+            r#"command = "show version""#,
+            r#"command = "show version", parser = "show_system", template = "show_version.textfsm""#,
+            1,
+        );
+        assert!(toml_content.contains("template"));
+
+        let error = TomlReader::read_from_str(&toml_content).unwrap_err();
+        assert!(error.to_string().contains("parser и template нельзя указывать вместе"));
+    }
+}
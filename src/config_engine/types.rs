@@ -1,3 +1,4 @@
+use crate::device::mode::CliMode;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -29,19 +30,16 @@ pub struct GlobalSettings {
     pub log_level: LogLevel,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
     Error,
     Warn,
+    #[default]
     Info,
     Debug,
 }
 
-impl Default for LogLevel {
-    fn default() -> Self { LogLevel::Info }
-}
-
 impl Default for GlobalSettings {
     fn default() -> Self {
         Self {
@@ -75,6 +73,8 @@ pub struct DeviceInfo {
     pub model: String,
     pub description: Option<String>,
     pub vendor: String,
+    /// Имя хоста в промпте устройства (для Eltex ESR по умолчанию - модель в нижнем регистре)
+    pub hostname: Option<String>,
 }
 
 /// Типы поддерживаемых устройств
@@ -91,10 +91,111 @@ pub enum DeviceType {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ConnectionConfig {
     pub transport: TransportType,
-    pub host: String,  // может быть IP, hostname, или device path
+    pub host: String,  // может быть IP, hostname, device path или команда (для pty)
     pub port: Option<u16>,
-    pub baud_rate: Option<u32>,
+    /// Скорость порта или "auto" для автоопределения (только serial)
+    pub baud_rate: Option<BaudRate>,
     pub timeout_seconds: Option<u64>,
+    /// Файл known_hosts для проверки ключа SSH сервера (по умолчанию ~/.ssh/known_hosts)
+    #[serde(default)]
+    pub known_hosts_path: Option<String>,
+    /// Для transport = "tcp": управлять портом консольного сервера по RFC 2217
+    /// (скорость из baud_rate применяется удаленно)
+    #[serde(default)]
+    pub rfc2217: bool,
+    /// Для transport = "serial": биты данных (5-8, по умолчанию 8)
+    #[serde(default)]
+    pub data_bits: Option<u8>,
+    /// Для transport = "serial": четность (по умолчанию none)
+    #[serde(default)]
+    pub parity: Option<Parity>,
+    /// Для transport = "serial": стоповые биты (1 или 2, по умолчанию 1)
+    #[serde(default)]
+    pub stop_bits: Option<u8>,
+    /// Для transport = "serial": управление потоком (по умолчанию none)
+    #[serde(default)]
+    pub flow_control: Option<FlowControl>,
+    /// Уровень линии DTR после открытия порта
+    #[serde(default)]
+    pub dtr: Option<bool>,
+    /// Уровень линии RTS после открытия порта
+    #[serde(default)]
+    pub rts: Option<bool>,
+    /// Сбросить DTR и RTS на указанное время после открытия порта
+    /// (некоторые USB адаптеры так "будят" консоль)
+    #[serde(default)]
+    pub toggle_dtr_rts_ms: Option<u64>,
+    /// Отправить сигнал break указанной длительности после подключения
+    #[serde(default)]
+    pub send_break_ms: Option<u64>,
+}
+
+/// Скорость последовательной линии: число или "auto"
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(try_from = "BaudRateValue", into = "BaudRateValue")]
+pub enum BaudRate {
+    Fixed(u32),
+    /// Перебрать распространенные скорости и выбрать ту, на которой ответ читаемый
+    Auto,
+}
+
+impl BaudRate {
+    /// Конкретная скорость, если она задана явно
+    pub fn fixed(&self) -> Option<u32> {
+        match self {
+            BaudRate::Fixed(rate) => Some(*rate),
+            BaudRate::Auto => None,
+        }
+    }
+}
+
+/// Представление BaudRate в конфигурации
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum BaudRateValue {
+    Number(u32),
+    Text(String),
+}
+
+impl TryFrom<BaudRateValue> for BaudRate {
+    type Error = String;
+
+    fn try_from(value: BaudRateValue) -> Result<Self, Self::Error> {
+        match value {
+            BaudRateValue::Number(rate) => Ok(BaudRate::Fixed(rate)),
+            BaudRateValue::Text(text) if text == "auto" => Ok(BaudRate::Auto),
+            BaudRateValue::Text(text) => Err(format!("baud_rate = \"{}\": ожидается число или \"auto\"", text)),
+        }
+    }
+}
+
+impl From<BaudRate> for BaudRateValue {
+    fn from(baud_rate: BaudRate) -> Self {
+        match baud_rate {
+            BaudRate::Fixed(rate) => BaudRateValue::Number(rate),
+            BaudRate::Auto => BaudRateValue::Text("auto".to_string()),
+        }
+    }
+}
+
+/// Четность последовательной линии
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Parity {
+    None,
+    Odd,
+    Even,
+}
+
+/// Управление потоком последовательной линии
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FlowControl {
+    None,
+    /// XON/XOFF
+    Software,
+    /// RTS/CTS
+    Hardware,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -103,6 +204,10 @@ pub enum TransportType {
     Serial,
     Telnet, 
     Ssh,
+    /// Порт консольного сервера по TCP (ser2net, Opengear и т.п.)
+    Tcp,
+    /// Локальная команда в псевдотерминале (host - командная строка)
+    Pty,
     Http,
     Https,
 }
@@ -134,6 +239,17 @@ pub enum StepType {
     Command { 
         command: String,
         expected_prompt: Option<String>,
+        /// Режим CLI, в который драйвер перейдет перед командой
+        /// (`"privileged"`, `"config"`, `{ sub_config = "interface Gi0/1" }`)
+        #[serde(default)]
+        mode: Option<CliMode>,
+        /// Парсер вывода из `ParserRegistry` (`"show_system"`, `"cisco_show_version"`, `"table"`):
+        /// разобранные записи сохраняются в результате рядом с выводом
+        #[serde(default)]
+        parser: Option<String>,
+        /// Путь к шаблону TextFSM: вывод разбирается в таблицу записей (вместо `parser`)
+        #[serde(default)]
+        template: Option<String>,
     },
     
     /// Ожидать определенный промпт
@@ -164,10 +280,11 @@ pub enum StepType {
     Logout,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ErrorAction {
     /// Остановить выполнение
+    #[default]
     Stop,
     /// Продолжить выполнение
     Continue,
@@ -177,11 +294,7 @@ pub enum ErrorAction {
     GotoStep(String),
 }
 
-impl Default for ErrorAction {
-    fn default() -> Self { ErrorAction::Stop }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct DeviceSettings {
     /// Специфичные промпты для устройства
     pub prompts: HashMap<String, String>,
@@ -195,16 +308,6 @@ pub struct DeviceSettings {
     pub init_commands: Vec<String>,
 }
 
-impl Default for DeviceSettings {
-    fn default() -> Self {
-        Self {
-            prompts: HashMap::new(),
-            extra_params: HashMap::new(),
-            init_commands: Vec::new(),
-        }
-    }
-}
-
 /// Сценарий выполнения (группа команд для нескольких устройств)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Scenario {
@@ -242,17 +345,14 @@ pub enum TargetDevices {
     All,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ExecutionMode {
+    #[default]
     Sequential,  // Последовательно
     Parallel,    // Параллельно
 }
 
-impl Default for ExecutionMode {
-    fn default() -> Self { ExecutionMode::Sequential }
-}
-
 // Вспомогательные функции для defaults
 fn default_max_retries() -> u32 { 3 }
 fn default_command_delay() -> u64 { 100 }
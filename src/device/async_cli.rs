@@ -0,0 +1,170 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::transport::{AsyncTransport, Regex};
+use crate::device::login::{self, LoginPrompt, LoginState};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use async_trait::async_trait;
+
+/// Асинхронный аналог DeviceCommands
+#[async_trait]
+pub trait AsyncDeviceCommands: Send {
+    async fn execute_command(&mut self, command: &str) -> Result<String>;
+    async fn disconnect(&mut self) -> Result<()>;
+
+    /// Выход из системы; после него устройство может не вернуть привычный промпт
+    async fn logout(&mut self) -> Result<String> {
+        self.execute_command("exit").await
+    }
+
+    /// Вход в систему; по умолчанию имя пользователя и пароль отправляются как команды
+    async fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        self.execute_command(&credentials.username).await?;
+        self.execute_command(&credentials.password).await
+    }
+}
+
+/// Индексы в `AsyncCliDevice::terminators`
+const CLI: usize = 0;
+const USERNAME_PROMPT: usize = 1;
+const PASSWORD_PROMPT: usize = 2;
+const PASSWORD_CHANGE_PROMPT: usize = 3;
+
+fn compile(what: &str, pattern: &str) -> Result<Regex> {
+    Regex::new(pattern)
+        .map_err(|e| Error::ConfigValidation(format!("Некорректный шаблон {}: {}", what, e)))
+}
+
+/// Устройство с командной строкой, работа с которым сводится к ожиданию промпта
+pub struct AsyncCliDevice<T: AsyncTransport> {
+    transport: T,
+    /// Промпт командной строки и приглашения входа
+    terminators: [Regex; 4],
+    normalizer: OutputNormalizer,
+    /// Какой промпт устройство показало последним
+    at: usize,
+}
+
+impl<T: AsyncTransport> AsyncCliDevice<T> {
+    /// `prompt` - регулярное выражение промпта (пробелы после него поглощаются автоматически)
+    pub fn new(transport: T, prompt: &str) -> Result<Self> {
+        Ok(Self {
+            transport,
+            terminators: [
+                compile("промпта", &format!(r"(?:{})[ \t]*", prompt))?,
+                compile("приглашения имени", login::DEFAULT_USERNAME_PROMPT)?,
+                compile("приглашения пароля", login::DEFAULT_PASSWORD_PROMPT)?,
+                compile("смены пароля", login::PASSWORD_CHANGE_PROMPT)?,
+            ],
+            normalizer: OutputNormalizer::new(),
+            at: CLI,
+        })
+    }
+
+    /// Свои приглашения ввода имени и пароля вместо встроенных
+    pub fn with_login_prompts(mut self, username: &str, password: &str) -> Result<Self> {
+        self.terminators[USERNAME_PROMPT] = compile("приглашения имени", username)?;
+        self.terminators[PASSWORD_PROMPT] = compile("приглашения пароля", password)?;
+        Ok(self)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Подключается и ждет первый промпт (командной строки или приглашение входа);
+    /// возвращает приветствие
+    pub async fn open(&mut self) -> Result<String> {
+        self.transport.connect().await?;
+        self.transport.send(b"\r\n").await?;
+
+        let timeout = self.transport.timeout();
+        let found = self.transport.expect(&self.terminators, timeout).await?;
+        self.at = found.index;
+        Ok(found.text())
+    }
+
+    /// Читает вывод до промпта; `command` - строка, эхо которой нужно убрать
+    async fn read(&mut self, command: &str) -> Result<String> {
+        let timeout = self.transport.timeout();
+        let found = self.normalizer.read_until_any_async(&mut self.transport, &self.terminators, timeout).await?;
+        self.at = found.index;
+        Ok(self.normalizer.clean(&found.before, command))
+    }
+
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            USERNAME_PROMPT => LoginPrompt::Username,
+            PASSWORD_PROMPT => LoginPrompt::Password,
+            PASSWORD_CHANGE_PROMPT => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+}
+
+#[async_trait]
+impl<T: AsyncTransport> AsyncDeviceCommands for AsyncCliDevice<T> {
+    async fn execute_command(&mut self, command: &str) -> Result<String> {
+        self.transport.send_string(command).await?;
+        self.read(command).await
+    }
+
+    /// Имя и пароль в ответ на приглашения, как `login::login_sequence`
+    async fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        let mut state = LoginState::new("CLI", credentials);
+        while let Some(line) = state.next_line(self.login_prompt())? {
+            self.transport.send_string(line).await?;
+            let text = self.read("").await?;
+            state.push_output(&text);
+        }
+        Ok(state.into_output())
+    }
+
+    async fn logout(&mut self) -> Result<String> {
+        self.transport.send_string("exit").await?;
+
+        // После выхода устройство показывает приглашение login: или закрывает соединение
+        let patterns = [
+            Regex::new(r"(?i)login:").expect("корректный шаблон"),
+            self.terminators[CLI].clone(),
+        ];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout).await {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout().await;
+        self.transport.disconnect().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::{BlockingTransport, MockTransport};
+
+    #[tokio::test]
+    async fn test_async_cli_device() {
+        let mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("Welcome\r\nesr-200# ")
+            .expect_command("show version")
+            .respond("show version\r\nSW version: 1.4.0\r\nesr-200# ")
+            .expect_command("exit");
+        let handle = mock.handle();
+
+        let mut device = AsyncCliDevice::new(BlockingTransport::new(mock), "esr-200#").unwrap();
+        assert!(device.open().await.unwrap().contains("Welcome"));
+        assert_eq!(device.execute_command("show version").await.unwrap(), "SW version: 1.4.0");
+        device.disconnect().await.unwrap();
+
+        assert!(!handle.is_connected());
+        handle.verify().unwrap();
+    }
+}
@@ -0,0 +1,344 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::mode::{self, CliMode, SubConfigTracker};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::{Regex, Transport};
+use std::sync::LazyLock;
+
+/// Промпт IOS в любом режиме: `sw1>`, `sw1#`, `sw1(config-if)#`; только с начала строки,
+/// чтобы `...sw#` внутри вывода не обрывал чтение
+pub(crate) const PROMPT: &str = r"(?m)^[\w.\-@/:]+(\([\w.\-/ ]*\))?[>#][ \t]*\z";
+
+const USERNAME_PROMPT: &str = r"(?i)username:[ \t]*$";
+
+const PASSWORD_PROMPT: &str = r"(?i)password:[ \t]*$";
+
+/// Сообщения IOS об ошибке команды
+const ERROR_MARKER: &str = r"^\s*% ?(Invalid input|Incomplete command|Ambiguous command|Unknown command|Unrecognized command|Bad |Access denied)";
+
+/// Индексы в `TERMINATORS`
+const CLI: usize = 0;
+const USERNAME: usize = 1;
+const PASSWORD: usize = 2;
+const PASSWORD_CHANGE: usize = 3;
+
+/// Промпты, на которых завершается чтение вывода (индексы выше)
+static TERMINATORS: LazyLock<[Regex; 4]> = LazyLock::new(|| {
+    [PROMPT, USERNAME_PROMPT, PASSWORD_PROMPT, login::PASSWORD_CHANGE_PROMPT]
+        .map(|pattern| Regex::new(pattern).expect("корректный шаблон"))
+});
+
+static ERROR: LazyLock<Regex> = LazyLock::new(|| Regex::new(ERROR_MARKER).expect("корректный шаблон"));
+
+/// Режим командной строки IOS, определяемый по промпту
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IosMode {
+    /// `sw1>`
+    User,
+    /// `sw1#`
+    Privileged,
+    /// `sw1(config)#` и вложенные режимы конфигурации
+    Config,
+}
+
+/// Драйвер Cisco IOS / IOS-XE
+pub struct CiscoIos<T: Transport> {
+    transport: T,
+    /// Какой промпт устройство показало последним (CLI, USERNAME или PASSWORD)
+    at: usize,
+    mode: IosMode,
+    sub_config: SubConfigTracker,
+    /// Пароль enable из учетных данных входа, для перехода в привилегированный режим
+    enable_password: Option<String>,
+    normalizer: OutputNormalizer,
+}
+
+impl<T: Transport> CiscoIos<T> {
+    /// Создает драйвер на подключенном транспорте и дожидается промпта
+    /// (командной строки или приглашения входа)
+    pub fn new(mut transport: T) -> Result<Self> {
+        transport.send(b"\r\n")?;
+
+        let mut device = Self {
+            transport,
+            at: CLI,
+            mode: IosMode::User,
+            sub_config: SubConfigTracker::default(),
+            enable_password: None,
+            normalizer: OutputNormalizer::new(),
+        };
+        device.read("")?;
+        Ok(device)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Текущий режим командной строки
+    pub fn mode(&self) -> IosMode {
+        self.mode
+    }
+
+    /// Читает вывод до промпта, запоминая, какой промпт пришел и в каком режиме CLI
+    fn read(&mut self, command: &str) -> Result<String> {
+        let timeout = self.transport.timeout();
+        let found = self.normalizer.read_until_any(&mut self.transport, &*TERMINATORS, timeout)?;
+
+        self.at = found.index;
+        if found.index == CLI {
+            let prompt = found.matched.trim_end();
+            self.mode = if prompt.contains("(config") {
+                IosMode::Config
+            } else if prompt.ends_with('#') {
+                IosMode::Privileged
+            } else {
+                IosMode::User
+            };
+            self.sub_config.observe(prompt, prompt.contains("(config-"));
+        }
+        Ok(self.normalizer.clean(&found.before, command))
+    }
+
+    /// Отправляет строку, не считая ее командой (пароль не попадает в эхо и проверки)
+    fn send_secret(&mut self, secret: &str) -> Result<String> {
+        self.transport.send_string(secret)?;
+        self.read("")
+    }
+
+    fn check(command: &str, output: String) -> Result<String> {
+        match output.lines().find(|line| ERROR.is_match(line)) {
+            Some(line) => Err(Error::Command(format!("{}: {}", command, line.trim()))),
+            None => Ok(output),
+        }
+    }
+
+    /// Переходит в привилегированный режим; пароль отправляется, если его запросят
+    pub fn enable(&mut self, password: Option<&str>) -> Result<String> {
+        if self.mode != IosMode::User {
+            return Ok(String::new());
+        }
+
+        self.transport.send_string("enable")?;
+        let mut output = self.read("enable")?;
+        if self.at == PASSWORD {
+            let password = password.ok_or_else(|| Error::ConfigValidation(
+                "Cisco запрашивает пароль enable: укажите credentials.enable_password".to_string()
+            ))?;
+            output = self.send_secret(password)?;
+        }
+
+        if self.at != CLI || self.mode != IosMode::Privileged {
+            return Err(Error::Command(format!("enable: не удалось войти в привилегированный режим {}", output.trim())));
+        }
+        Ok(output)
+    }
+
+    /// Входит в `configure terminal`
+    pub fn configure(&mut self) -> Result<String> {
+        if self.mode == IosMode::Config {
+            return Ok(String::new());
+        }
+        let output = self.execute_command("configure terminal")?;
+        if self.mode != IosMode::Config {
+            return Err(Error::Command(format!("configure terminal: режим конфигурации недоступен {}", output.trim())));
+        }
+        Ok(output)
+    }
+
+    /// Выходит из режима конфигурации (`end`)
+    pub fn end_configure(&mut self) -> Result<String> {
+        if self.mode != IosMode::Config {
+            return Ok(String::new());
+        }
+        self.execute_command("end")
+    }
+
+    /// Выполняет команды в режиме конфигурации и выходит из него даже при ошибке
+    pub fn execute_config(&mut self, commands: &[&str]) -> Result<String> {
+        self.configure()?;
+
+        let mut output = Vec::new();
+        for command in commands {
+            match self.execute_command(command) {
+                Ok(text) => output.push(text),
+                Err(e) => {
+                    let _ = self.end_configure();
+                    return Err(e);
+                }
+            }
+        }
+        self.end_configure()?;
+
+        Ok(output.into_iter().filter(|text| !text.is_empty()).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+impl<T: Transport> LoginSteps for CiscoIos<T> {
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            USERNAME => LoginPrompt::Username,
+            PASSWORD => LoginPrompt::Password,
+            PASSWORD_CHANGE => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        self.send_secret(line)
+    }
+}
+
+impl<T: Transport> DeviceCommands for CiscoIos<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        self.transport.send_string(command)?;
+        let output = self.read(command)?;
+        Self::check(command, output)
+    }
+
+    /// `Username:`/`Password:` (если спросят), затем enable и `terminal length 0`
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        self.enable_password = credentials.enable_password.clone();
+        login::login_sequence(self, "Cisco", credentials)?;
+        self.enable(credentials.enable_password.as_deref())?;
+        self.execute_command("terminal length 0")
+    }
+
+    fn cli_mode(&self) -> Option<CliMode> {
+        Some(match self.mode {
+            IosMode::User => CliMode::User,
+            IosMode::Privileged => CliMode::Privileged,
+            IosMode::Config => self.sub_config.mode().unwrap_or(CliMode::Config),
+        })
+    }
+
+    fn mode_transition(&mut self, to: &CliMode) -> Result<String> {
+        match (self.cli_mode(), to) {
+            (Some(CliMode::User), CliMode::Privileged) => {
+                let password = self.enable_password.clone();
+                self.enable(password.as_deref())
+            }
+            (Some(CliMode::Privileged), CliMode::User) => self.execute_command("disable"),
+            (Some(CliMode::Privileged), CliMode::Config) => self.configure(),
+            (Some(CliMode::Config), CliMode::Privileged) => self.end_configure(),
+            (Some(CliMode::Config), CliMode::SubConfig(command)) => {
+                let output = self.execute_command(command)?;
+                self.sub_config.entered(command);
+                Ok(output)
+            }
+            (Some(CliMode::SubConfig(_)), CliMode::Config) => self.execute_command("exit"),
+            (from, to) => Err(mode::no_transition("Cisco", from, to)),
+        }
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        if self.mode == IosMode::Config {
+            self.end_configure()?;
+        }
+        self.transport.send_string("exit")?;
+
+        // После exit консоль ждет Enter или приглашения входа, telnet/SSH закрывают соединение
+        let patterns = [Regex::new(r"(?i)press return|username:|login:").expect("корректный шаблон")];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout();
+        self.transport.disconnect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    fn credentials() -> CredentialsConfig {
+        CredentialsConfig {
+            username: "admin".to_string(),
+            password: "cisco123".to_string(),
+            enable_password: Some("enable456".to_string()),
+            ssh_key_path: None,
+        }
+    }
+
+    #[test]
+    fn test_login_enable_and_config() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nUser Access Verification\r\n\r\nUsername: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("cisco123")
+            .respond("\r\nsw1>")
+            .expect_command("enable")
+            .respond("enable\r\nPassword: ")
+            .expect_command("enable456")
+            .respond("\r\nsw1#")
+            .expect_command("terminal length 0")
+            .respond("terminal length 0\r\nsw1#")
+            .expect_command("configure terminal")
+            .respond("configure terminal\r\nEnter configuration commands, one per line.  End with CNTL/Z.\r\nsw1(config)#")
+            .expect_command("interface Gi0/1")
+            .respond("interface Gi0/1\r\nsw1(config-if)#")
+            .expect_command("shutdwn")
+            .respond("shutdwn\r\n      ^\r\n% Invalid input detected at '^' marker.\r\n\r\nsw1(config-if)#")
+            .expect_command("end")
+            .respond("end\r\nsw1#")
+            .expect_command("exit");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let mut device = CiscoIos::new(mock).unwrap();
+        device.login(&credentials()).unwrap();
+        assert_eq!(device.mode(), IosMode::Privileged);
+
+        match device.execute_config(&["interface Gi0/1", "shutdwn"]) {
+            Err(Error::Command(message)) => assert!(message.contains("% Invalid input")),
+            other => panic!("ожидалась ошибка команды, получено {:?}", other),
+        }
+        assert_eq!(device.mode(), IosMode::Privileged);
+
+        device.disconnect().unwrap();
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_prompt_only_at_line_start() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nsw1#")
+            .expect_command("show running-config | include banner")
+            .respond_chunks(&["show running-config | include banner\r\nbanner motd ^Cwelcome to sw#", "1^C\r\nsw1#"]);
+        mock.connect().unwrap();
+
+        let mut device = CiscoIos::new(mock).unwrap();
+        let output = device.execute_command("show running-config | include banner").unwrap();
+        assert_eq!(output, "banner motd ^Cwelcome to sw#1^C");
+    }
+
+    #[test]
+    fn test_login_rejected() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("Username: ")
+            .expect_command("admin")
+            .respond("Password: ")
+            .expect_command("cisco123")
+            .respond("\r\n% Authentication failed\r\n\r\nUsername: ");
+        mock.connect().unwrap();
+
+        let mut device = CiscoIos::new(mock).unwrap();
+        assert!(matches!(device.login(&credentials()), Err(Error::Authentication(_))));
+    }
+}
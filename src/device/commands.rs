@@ -1,9 +1,55 @@
-use crate::error::types::Result;
+use crate::config_engine::types::CredentialsConfig;
+use crate::device::mode::{mode_path, CliMode, MAX_SUB_CONFIG_DEPTH};
+use crate::error::types::{Error, Result};
 
 pub trait DeviceCommands {
     fn execute_command(&mut self, command: &str) -> Result<String>;
     fn disconnect(&mut self) -> Result<()>;
-}
 
+    /// Выход из системы; после него устройство может не вернуть привычный промпт
+    fn logout(&mut self) -> Result<String> {
+        self.execute_command("exit")
+    }
+
+    /// Вход в систему; по умолчанию имя пользователя и пароль отправляются как команды
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        self.execute_command(&credentials.username)?;
+        self.execute_command(&credentials.password)
+    }
+
+    /// Текущий режим CLI; `None`, если драйвер не различает режимы
+    fn cli_mode(&self) -> Option<CliMode> {
+        None
+    }
 
+    /// Переход в соседний режим (на уровень выше или ниже текущего)
+    fn mode_transition(&mut self, to: &CliMode) -> Result<String> {
+        Err(Error::Command(format!("переход в режим {} не поддерживается драйвером", to)))
+    }
 
+    /// Переходит в режим `target` через соседние режимы
+    fn navigate_to(&mut self, target: &CliMode) -> Result<String> {
+        let current = self.cli_mode().ok_or_else(|| Error::Command(
+            format!("драйвер не различает режимы CLI, переход в режим {} невозможен", target)
+        ))?;
+
+        let mut output = Vec::new();
+        for mode in mode_path(&current, target) {
+            output.push(self.mode_transition(&mode)?);
+            // Выход из вложенного режима поднимает на один уровень, вложенных может быть несколько
+            let mut depth = 1;
+            while mode == CliMode::Config && matches!(self.cli_mode(), Some(CliMode::SubConfig(_))) && depth < MAX_SUB_CONFIG_DEPTH {
+                output.push(self.mode_transition(&mode)?);
+                depth += 1;
+            }
+        }
+        match self.cli_mode() {
+            Some(mode) if &mode == target => {
+                Ok(output.into_iter().filter(|text| !text.is_empty()).collect::<Vec<_>>().join("\n"))
+            }
+            mode => Err(Error::Command(format!(
+                "режим {} не достигнут, CLI в режиме {}", target, mode.map(|mode| mode.to_string()).unwrap_or_default()
+            ))),
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use crate::config_engine::types::{CredentialsConfig, DeviceInfo};
+use crate::transport::{Regex, Transport};
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::mode::{self, CliMode, SubConfigTracker};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::parser::{parse_show_system, SystemInfo};
+use std::sync::LazyLock;
+
+const LOGIN_PROMPT: &str = r"(?i)login:[ \t]*$";
+
+const PASSWORD_PROMPT: &str = r"(?i)password:[ \t]*$";
+
+/// Сообщения ESR об ошибке команды: `% Unrecognized command`, `Error: ...`,
+/// `Configuration commit failed`. Строки вывода вроде `Errors: 0` не совпадают
+const ERROR_MARKERS: &[&str] = &[
+    r"^\s*% ?(Unrecognized|Unknown|Invalid|Incomplete|Ambiguous|Wrong|Bad|Error|Failed)\b",
+    r"^\s*(Syntax error|Error:)",
+    r"(?i)\b(commit|confirm|rollback) (failed|error)",
+];
+
+/// Имя хоста в промпте: `DeviceInfo.hostname` или модель в нижнем регистре
+/// (заводской промпт ESR-200 - `esr-200#`)
+pub fn hostname_for(info: &DeviceInfo) -> String {
+    info.hostname.clone().unwrap_or_else(|| info.model.to_lowercase())
+}
+
+/// Регулярное выражение промпта ESR во всех режимах: `esr-200#`, `esr-200(config-if-gi)#`;
+/// только с начала строки и в конце вывода, чтобы `esr-200#` внутри вывода не обрывал чтение
+pub fn prompt_pattern(hostname: &str) -> String {
+    format!(r"(?m)^{}(\([\w-]+\))?[#>][ \t]*\z", regex::escape(hostname))
+}
+
+/// Индексы в `EltexEsr::terminators`
+const CLI: usize = 0;
+const LOGIN: usize = 1;
+const PASSWORD: usize = 2;
+const PASSWORD_CHANGE: usize = 3;
+
+static ERRORS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    ERROR_MARKERS.iter().map(|pattern| Regex::new(pattern).expect("корректный шаблон")).collect()
+});
+
+/// Драйвер маршрутизаторов Eltex ESR (ESR-10/20/100/200/1000 и др.).
+/// Конфигурация меняется в кандидат-режиме: `configure` → `end` → `commit` → `confirm`;
+/// без `confirm` ESR сам откатит изменения по таймеру, `rollback` отменяет их сразу
+pub struct EltexEsr<T: Transport> {
+    transport: T,
+    /// Промпт командной строки и приглашения входа (индексы выше)
+    terminators: [Regex; 4],
+    /// Какой промпт устройство показало последним
+    at: usize,
+    in_config: bool,
+    privileged: bool,
+    sub_config: SubConfigTracker,
+    normalizer: OutputNormalizer,
+}
+
+impl<T: Transport> EltexEsr<T> {
+    pub fn new(mut transport: T, hostname: &str) -> Result<Self> {
+        // Отправляем Enter для получения приглашения
+        transport.send(b"\r\n")?;
+
+        let prompt = Regex::new(&prompt_pattern(hostname))
+            .map_err(|e| Error::ConfigValidation(format!("Некорректное имя хоста '{}': {}", hostname, e)))?;
+
+        let mut device = Self {
+            transport,
+            terminators: [
+                prompt,
+                Regex::new(LOGIN_PROMPT).expect("корректный шаблон"),
+                Regex::new(PASSWORD_PROMPT).expect("корректный шаблон"),
+                Regex::new(login::PASSWORD_CHANGE_PROMPT).expect("корректный шаблон"),
+            ],
+            at: CLI,
+            in_config: false,
+            privileged: true,
+            sub_config: SubConfigTracker::default(),
+            normalizer: OutputNormalizer::new(),
+        };
+
+        // Читаем приветствие до промпта (или до приглашения входа)
+        device.read("")?;
+        Ok(device)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Читает ВСЕ данные до промпта, листая постраничный вывод
+    fn read(&mut self, command: &str) -> Result<String> {
+        let timeout = self.transport.timeout();
+        let found = self.normalizer.read_until_any(&mut self.transport, &self.terminators, timeout)?;
+        self.at = found.index;
+        if found.index == CLI {
+            let prompt = found.matched.trim_end();
+            self.in_config = prompt.contains("(config");
+            self.privileged = prompt.ends_with('#');
+            self.sub_config.observe(prompt, prompt.contains("(config-"));
+        }
+        Ok(self.normalizer.clean(&found.before, command))
+    }
+
+    fn check(command: &str, output: String) -> Result<String> {
+        match output.lines().find(|line| ERRORS.iter().any(|marker| marker.is_match(line))) {
+            Some(line) => Err(Error::Command(format!("{}: {}", command, line.trim()))),
+            None => Ok(output),
+        }
+    }
+
+    /// Находится ли CLI в режиме конфигурации
+    pub fn in_config(&self) -> bool {
+        self.in_config
+    }
+
+    /// Входит в режим конфигурации (кандидат-конфигурация)
+    pub fn configure(&mut self) -> Result<String> {
+        if self.in_config {
+            return Ok(String::new());
+        }
+        let output = self.execute_command("configure")?;
+        if !self.in_config {
+            return Err(Error::Command(format!("configure: режим конфигурации недоступен {}", output.trim())));
+        }
+        Ok(output)
+    }
+
+    /// Выходит из режима конфигурации (`end`)
+    pub fn end_configure(&mut self) -> Result<String> {
+        if !self.in_config {
+            return Ok(String::new());
+        }
+        self.execute_command("end")
+    }
+
+    /// Применяет кандидат-конфигурацию; запускается таймер подтверждения
+    pub fn commit(&mut self) -> Result<String> {
+        self.end_configure()?;
+        self.execute_command("commit")
+    }
+
+    /// Подтверждает примененную конфигурацию, таймер отката останавливается
+    pub fn confirm(&mut self) -> Result<String> {
+        self.execute_command("confirm")
+    }
+
+    /// Отменяет непримененные изменения кандидат-конфигурации
+    pub fn rollback(&mut self) -> Result<String> {
+        self.end_configure()?;
+        self.execute_command("rollback")
+    }
+
+    /// Выполняет команды в режиме конфигурации, затем `commit` и `confirm`.
+    /// При ошибке изменения откатываются
+    pub fn apply_config(&mut self, commands: &[&str]) -> Result<String> {
+        self.configure()?;
+
+        let mut output = Vec::new();
+        let result = commands.iter()
+            .try_for_each(|command| self.execute_command(command).map(|text| output.push(text)))
+            .and_then(|_| self.commit().map(|text| output.push(text)));
+        if let Err(e) = result {
+            let _ = self.rollback();
+            return Err(e);
+        }
+        output.push(self.confirm()?);
+
+        Ok(output.into_iter().filter(|text| !text.is_empty()).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+impl<T: Transport> LoginSteps for EltexEsr<T> {
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            LOGIN => LoginPrompt::Username,
+            PASSWORD => LoginPrompt::Password,
+            PASSWORD_CHANGE => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        self.transport.send_string(line)?;
+        self.read("")
+    }
+}
+
+// Реализация трейта DeviceCommands
+impl<T: Transport> DeviceCommands for EltexEsr<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        self.transport.send_string(command)?;
+        let response = self.read(command)?;
+        Self::check(command, response)
+    }
+
+    fn cli_mode(&self) -> Option<CliMode> {
+        Some(if self.in_config {
+            self.sub_config.mode().unwrap_or(CliMode::Config)
+        } else if self.privileged {
+            CliMode::Privileged
+        } else {
+            CliMode::User
+        })
+    }
+
+    fn mode_transition(&mut self, to: &CliMode) -> Result<String> {
+        match (self.cli_mode(), to) {
+            (Some(CliMode::User), CliMode::Privileged) => self.execute_command("enable"),
+            (Some(CliMode::Privileged), CliMode::Config) => self.configure(),
+            (Some(CliMode::Config), CliMode::Privileged) => self.end_configure(),
+            (Some(CliMode::Config), CliMode::SubConfig(command)) => {
+                let output = self.execute_command(command)?;
+                self.sub_config.entered(command);
+                Ok(output)
+            }
+            (Some(CliMode::SubConfig(_)), CliMode::Config) => self.execute_command("exit"),
+            (from, to) => Err(mode::no_transition("ESR", from, to)),
+        }
+    }
+
+    /// `login:`/`Password:` (если спросят); открытая командная строка - вход уже выполнен
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        login::login_sequence(self, "ESR", credentials)
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        if self.in_config {
+            self.end_configure()?;
+        }
+        self.transport.send_string("exit")?;
+
+        // После выхода ESR показывает приглашение login: или закрывает соединение
+        let patterns = [self.terminators[LOGIN].clone(), self.terminators[CLI].clone()];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        // Сначала выходим из системы
+        let _ = self.logout();
+
+        // Потом разрываем соединение
+        self.transport.disconnect()
+    }
+}
+
+// Специфичные методы для Eltex
+impl<T: Transport> EltexEsr<T> {
+    pub fn show_system(&mut self) -> Result<String> {
+        let response = self.execute_command("show system")?;
+
+
+        let cleaned = response
+            .lines()
+            .skip_while(|line| line.trim().is_empty() || line.contains("show system"))
+            .take_while(|line| !self.terminators[CLI].is_match(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok(cleaned.trim().to_string())
+    }
+
+    /// `show system`, разобранный в запись
+    pub fn system_info(&mut self) -> Result<SystemInfo> {
+        let output = self.show_system()?;
+        parse_show_system(&output)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[test]
+    fn test_show_system_with_mock() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200# ")
+            .expect_command("show system")
+            .respond_chunks(&["show system\r\n", "System type: ESR-200\r\n", "Uptime: 1 day\r\nesr-200# "]);
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let mut device = EltexEsr::new(mock, "esr-200").unwrap();
+        let system = device.show_system().unwrap();
+
+        assert_eq!(system, "System type: ESR-200\nUptime: 1 day");
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_prompt_only_at_line_start() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200# ")
+            .expect_command("show running-config")
+            .respond_chunks(&["show running-config\r\n  description \"uplink esr-200#", "core\"\r\nesr-200# "]);
+        mock.connect().unwrap();
+
+        let mut device = EltexEsr::new(mock, "esr-200").unwrap();
+        let output = device.execute_command("show running-config").unwrap();
+        assert_eq!(output, "  description \"uplink esr-200#core\"");
+    }
+
+    #[test]
+    fn test_system_info_with_mock() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nesr-200# ")
+            .expect_command("show system")
+            .respond("show system\r\nSystem type:      Eltex Service Router ESR-200\r\nSystem uptime:    1 days, 2 hours\r\nesr-200# ");
+        mock.connect().unwrap();
+
+        let info = EltexEsr::new(mock, "esr-200").unwrap().system_info().unwrap();
+        assert_eq!(info.system_type.as_deref(), Some("Eltex Service Router ESR-200"));
+        assert_eq!(info.uptime.as_deref(), Some("1 days, 2 hours"));
+    }
+
+    #[test]
+    fn test_check_matches_esr_errors_only() {
+        let check = |output: &str| EltexEsr::<MockTransport>::check("cmd", output.to_string());
+
+        assert!(check("Input errors: 0\nErrors: 0\n  description error-free uplink").is_ok());
+        assert!(check("%LINK-UPDOWN: gi1/0/1 changed state to up\n% 2 messages suppressed").is_ok());
+        assert!(check("                ^\n% Unrecognized command").is_err());
+        assert!(check("Error: Configuration is not valid").is_err());
+        assert!(check("Configuration commit failed").is_err());
+    }
+
+    #[test]
+    fn test_apply_config_rolls_back_on_error() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\ncore-rt1# ")
+            .expect_command("configure")
+            .respond("configure\r\ncore-rt1(config)# ")
+            .expect_command("interface gigabitethernet 1/0/1")
+            .respond("interface gigabitethernet 1/0/1\r\ncore-rt1(config-if-gi)# ")
+            .expect_command("descripton uplink")
+            .respond("descripton uplink\r\n% Unrecognized command\r\ncore-rt1(config-if-gi)# ")
+            .expect_command("end")
+            .respond("end\r\ncore-rt1# ")
+            .expect_command("rollback")
+            .respond("rollback\r\nConfiguration has been successfully restored\r\ncore-rt1# ")
+            .expect_command("configure")
+            .respond("configure\r\ncore-rt1(config)# ")
+            .expect_command("hostname core-rt1")
+            .respond("hostname core-rt1\r\ncore-rt1(config)# ")
+            .expect_command("end")
+            .respond("end\r\ncore-rt1# ")
+            .expect_command("commit")
+            .respond("commit\r\nConfiguration has been successfully applied and saved to flash. Commit timer started, changes will be reverted in 600 seconds.\r\ncore-rt1# ")
+            .expect_command("confirm")
+            .respond("confirm\r\nConfiguration has been confirmed. Commit timer canceled.\r\ncore-rt1# ");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let mut device = EltexEsr::new(mock, "core-rt1").unwrap();
+        match device.apply_config(&["interface gigabitethernet 1/0/1", "descripton uplink"]) {
+            Err(Error::Command(message)) => assert!(message.contains("% Unrecognized command")),
+            other => panic!("ожидалась ошибка команды, получено {:?}", other),
+        }
+        assert!(!device.in_config());
+
+        let output = device.apply_config(&["hostname core-rt1"]).unwrap();
+        assert!(output.contains("Commit timer canceled"));
+        handle.verify().unwrap();
+    }
+}
@@ -0,0 +1,328 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::mode::{self, CliMode, SubConfigTracker};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::{Regex, Transport};
+use std::sync::LazyLock;
+
+/// Промпт MES в любом режиме: `console>`, `console#`, `console(config-if)#`; только с начала строки,
+/// чтобы `...word#` внутри вывода не обрывал чтение
+pub(crate) const PROMPT: &str = r"(?m)^[\w.\-]+(\([\w.\-/ ]*\))?[>#][ \t]*\z";
+
+const USERNAME_PROMPT: &str = r"(?i)user ?name:[ \t]*$";
+
+const PASSWORD_PROMPT: &str = r"(?i)password:[ \t]*$";
+
+/// Запрос подтверждения: `Overwrite file [startup-config].... (Y/N)[N] ?`
+const CONFIRM_PROMPT: &str = r"(?i)\(y/n\)(\[[yn]\])?[ \t]*\??[ \t]*$";
+
+/// Сообщения MES об ошибке команды
+const ERROR_MARKER: &str = r"(?i)^\s*(% ?(Unrecognized command|Invalid input|Incomplete command|Wrong number of parameters|Bad |Error)|Error:|Copy failed)";
+
+/// Индексы в `TERMINATORS`
+const CLI: usize = 0;
+const USERNAME: usize = 1;
+const PASSWORD: usize = 2;
+const CONFIRM: usize = 3;
+const PASSWORD_CHANGE: usize = 4;
+
+static ERROR: LazyLock<Regex> = LazyLock::new(|| Regex::new(ERROR_MARKER).expect("корректный шаблон"));
+
+/// Промпты, на которых завершается чтение вывода (индексы выше)
+static TERMINATORS: LazyLock<[Regex; 5]> = LazyLock::new(|| {
+    [PROMPT, USERNAME_PROMPT, PASSWORD_PROMPT, CONFIRM_PROMPT, login::PASSWORD_CHANGE_PROMPT]
+        .map(|pattern| Regex::new(pattern).expect("корректный шаблон"))
+});
+
+/// Драйвер коммутаторов Eltex MES (MES1124, MES2324, MES3324 и др.)
+pub struct EltexMes<T: Transport> {
+    transport: T,
+    /// Какой промпт устройство показало последним
+    at: usize,
+    privileged: bool,
+    in_config: bool,
+    sub_config: SubConfigTracker,
+    /// Пароль enable из учетных данных входа, для перехода в привилегированный режим
+    enable_password: Option<String>,
+    /// Ответ на запросы `(Y/N)`
+    confirm_answer: String,
+    normalizer: OutputNormalizer,
+}
+
+impl<T: Transport> EltexMes<T> {
+    /// Создает драйвер на подключенном транспорте и дожидается промпта
+    /// (командной строки или приглашения входа)
+    pub fn new(mut transport: T) -> Result<Self> {
+        transport.send(b"\r\n")?;
+
+        let mut device = Self {
+            transport,
+            at: CLI,
+            privileged: false,
+            in_config: false,
+            sub_config: SubConfigTracker::default(),
+            enable_password: None,
+            confirm_answer: "Y".to_string(),
+            normalizer: OutputNormalizer::new(),
+        };
+        device.read("")?;
+        Ok(device)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Ответ на запросы `(Y/N)` (по умолчанию `Y` - подтверждать)
+    pub fn with_confirm_answer(mut self, answer: &str) -> Self {
+        self.confirm_answer = answer.to_string();
+        self
+    }
+
+    /// Читает вывод до промпта, отвечая на запросы подтверждения
+    fn read(&mut self, command: &str) -> Result<String> {
+        let timeout = self.transport.timeout();
+        let mut raw = String::new();
+        loop {
+            let found = self.normalizer.read_until_any(&mut self.transport, &*TERMINATORS, timeout)?;
+            raw.push_str(&found.before);
+            self.at = found.index;
+
+            match found.index {
+                CONFIRM => {
+                    raw.push_str(&found.matched);
+                    // MES принимает ответ одной клавишей, без Enter
+                    self.transport.send(self.confirm_answer.as_bytes())?;
+                }
+                CLI => {
+                    let prompt = found.matched.trim_end();
+                    self.in_config = prompt.contains("(config");
+                    self.privileged = prompt.ends_with('#');
+                    self.sub_config.observe(prompt, prompt.contains("(config-"));
+                    break;
+                }
+                _ => break,
+            }
+        }
+        Ok(self.normalizer.clean(&raw, command))
+    }
+
+    fn send_secret(&mut self, secret: &str) -> Result<String> {
+        self.transport.send_string(secret)?;
+        self.read("")
+    }
+
+    fn check(command: &str, output: String) -> Result<String> {
+        match output.lines().find(|line| ERROR.is_match(line)) {
+            Some(line) => Err(Error::Command(format!("{}: {}", command, line.trim()))),
+            None => Ok(output),
+        }
+    }
+
+    /// Переходит в привилегированный режим; пароль отправляется, если его запросят
+    pub fn enable(&mut self, password: Option<&str>) -> Result<String> {
+        if self.privileged {
+            return Ok(String::new());
+        }
+
+        self.transport.send_string("enable")?;
+        let mut output = self.read("enable")?;
+        if self.at == PASSWORD {
+            let password = password.ok_or_else(|| Error::ConfigValidation(
+                "MES запрашивает пароль enable: укажите credentials.enable_password".to_string()
+            ))?;
+            output = self.send_secret(password)?;
+        }
+
+        if self.at != CLI || !self.privileged {
+            return Err(Error::Command(format!("enable: не удалось войти в привилегированный режим {}", output.trim())));
+        }
+        Ok(output)
+    }
+
+    /// Входит в `configure`
+    pub fn configure(&mut self) -> Result<String> {
+        if self.in_config {
+            return Ok(String::new());
+        }
+        let output = self.execute_command("configure")?;
+        if !self.in_config {
+            return Err(Error::Command(format!("configure: режим конфигурации недоступен {}", output.trim())));
+        }
+        Ok(output)
+    }
+
+    /// Выходит из режима конфигурации (`end`)
+    pub fn end_configure(&mut self) -> Result<String> {
+        if !self.in_config {
+            return Ok(String::new());
+        }
+        self.execute_command("end")
+    }
+
+    /// Сохраняет конфигурацию: `copy running-config startup-config` с подтверждением
+    pub fn save_config(&mut self) -> Result<String> {
+        self.end_configure()?;
+        self.execute_command("copy running-config startup-config")
+    }
+}
+
+impl<T: Transport> LoginSteps for EltexMes<T> {
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            USERNAME => LoginPrompt::Username,
+            PASSWORD => LoginPrompt::Password,
+            PASSWORD_CHANGE => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        self.send_secret(line)
+    }
+}
+
+impl<T: Transport> DeviceCommands for EltexMes<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        self.transport.send_string(command)?;
+        let output = self.read(command)?;
+        Self::check(command, output)
+    }
+
+    /// `User Name:`/`Password:` (если спросят), затем enable и `terminal datadump`
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        self.enable_password = credentials.enable_password.clone();
+        login::login_sequence(self, "MES", credentials)?;
+
+        self.enable(credentials.enable_password.as_deref())?;
+        self.execute_command("terminal datadump")
+    }
+
+    fn cli_mode(&self) -> Option<CliMode> {
+        Some(if self.in_config {
+            self.sub_config.mode().unwrap_or(CliMode::Config)
+        } else if self.privileged {
+            CliMode::Privileged
+        } else {
+            CliMode::User
+        })
+    }
+
+    fn mode_transition(&mut self, to: &CliMode) -> Result<String> {
+        match (self.cli_mode(), to) {
+            (Some(CliMode::User), CliMode::Privileged) => {
+                let password = self.enable_password.clone();
+                self.enable(password.as_deref())
+            }
+            (Some(CliMode::Privileged), CliMode::User) => self.execute_command("disable"),
+            (Some(CliMode::Privileged), CliMode::Config) => self.configure(),
+            (Some(CliMode::Config), CliMode::Privileged) => self.end_configure(),
+            (Some(CliMode::Config), CliMode::SubConfig(command)) => {
+                let output = self.execute_command(command)?;
+                self.sub_config.entered(command);
+                Ok(output)
+            }
+            (Some(CliMode::SubConfig(_)), CliMode::Config) => self.execute_command("exit"),
+            (from, to) => Err(mode::no_transition("MES", from, to)),
+        }
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        if self.in_config {
+            self.end_configure()?;
+        }
+        self.transport.send_string("exit")?;
+
+        let patterns = [Regex::new(USERNAME_PROMPT).expect("корректный шаблон")];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout();
+        self.transport.disconnect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[test]
+    fn test_login_and_save_config() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\n\r\nUser Name:")
+            .expect_command("admin")
+            .respond("admin\r\nPassword:")
+            .expect_command("admin")
+            .respond("\r\n\r\nconsole#")
+            .expect_command("terminal datadump")
+            .respond("terminal datadump\r\nconsole#")
+            .expect_command("configure")
+            .respond("configure\r\nconsole(config)#")
+            .expect_command("vlan database")
+            .respond("vlan database\r\nconsole(config-vlan)#")
+            .expect_command("end")
+            .respond("end\r\nconsole#")
+            .expect_command("copy running-config startup-config")
+            .respond("copy running-config startup-config\r\nOverwrite file [startup-config].... (Y/N)[N] ?")
+            .expect_bytes("Y")
+            .respond("Y\r\n\r\n16-May-2024 10:00:00 %COPY-I-FILECPY: Files Copy - source URL running-config destination URL flash://startup-config\r\nCopy succeeded\r\nconsole#");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let credentials = CredentialsConfig {
+            username: "admin".to_string(),
+            password: "admin".to_string(),
+            enable_password: None,
+            ssh_key_path: None,
+        };
+
+        let mut device = EltexMes::new(mock).unwrap();
+        device.login(&credentials).unwrap();
+        device.configure().unwrap();
+        device.execute_command("vlan database").unwrap();
+
+        let output = device.save_config().unwrap();
+        assert!(output.contains("Copy succeeded"));
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_prompt_only_at_line_start() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("console#")
+            .expect_command("show interfaces description")
+            .respond_chunks(&["show interfaces description\r\ngi1/0/1   to core>", "uplink\r\nconsole#"]);
+        mock.connect().unwrap();
+
+        let mut device = EltexMes::new(mock).unwrap();
+        let output = device.execute_command("show interfaces description").unwrap();
+        assert_eq!(output, "gi1/0/1   to core>uplink");
+    }
+
+    #[test]
+    fn test_unrecognized_command() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("console#")
+            .expect_command("show vlna")
+            .respond("show vlna\r\n                                 ^\r\n% Unrecognized command\r\nconsole#");
+        mock.connect().unwrap();
+
+        let mut device = EltexMes::new(mock).unwrap();
+        assert!(matches!(device.execute_command("show vlna"), Err(Error::Command(_))));
+    }
+}
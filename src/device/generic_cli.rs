@@ -0,0 +1,360 @@
+use crate::config_engine::types::{CredentialsConfig, DeviceSettings};
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::mode::{self, CliMode, SubConfigTracker};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::{Regex, Transport};
+use std::collections::HashMap;
+
+/// Сообщения об ошибках, которые распознаются по умолчанию
+const DEFAULT_ERROR_MARKERS: &[&str] = &[
+    r"(?m)^\s*% ?(Invalid|Incomplete|Ambiguous|Unknown|Unrecognized|Bad|Error)",
+    r"(?im)^\s*(syntax error|unknown command|invalid command|command not found)",
+];
+
+/// Промпты режимов по умолчанию (соглашение Cisco): `sw(config-if)#`, `sw(config)#`, `sw#`, `sw>`
+const DEFAULT_MODE_PROMPTS: &[(&str, &str)] = &[
+    ("sub_config", r"\(config-[^)]*\)[>#]?[ \t]*$"),
+    ("config", r"\(config\)[>#]?[ \t]*$"),
+    ("privileged", r"#[ \t]*$"),
+    ("user", r">[ \t]*$"),
+];
+
+/// Описание CLI устройства из `DeviceSettings`.
+///
+/// `prompts` (регулярные выражения):
+/// - `main` - промпт командной строки (обязателен; все режимы, например `[\w-]+(\(config[^)]*\))?#`)
+/// - `username`/`login`, `password` - приглашения входа
+/// - `pager*`, `pager_answer` - постраничный вывод (см. `OutputNormalizer`)
+/// - `error*` - признаки ошибки в выводе команды (заменяют встроенные)
+/// - `mode_user`, `mode_privileged`, `mode_config`, `mode_sub_config` - по какому промпту
+///   узнать режим (по умолчанию `>`, `#`, `(config)`, `(config-...)`)
+///
+/// `extra_params`:
+/// - `mode_<имя>` - команда перехода в режим: `mode_privileged` (из `user`), `mode_config`
+///   (из `privileged`), `mode_user` (из `privileged`), `mode_exit` - выход из режима
+///   конфигурации (по умолчанию `exit`); другие имена доступны через `GenericCli::enter_mode`
+/// - `logout_command` - команда выхода (по умолчанию `exit`)
+///
+/// Пароль, если команда режима его запросит, берется из `credentials.enable_password`
+#[derive(Debug, Clone)]
+pub struct CliProfile {
+    /// Промпты, на которых завершается чтение вывода: основной и приглашения входа
+    terminators: [Regex; 4],
+    errors: Vec<Regex>,
+    modes: HashMap<String, String>,
+    mode_prompts: Vec<(CliMode, Regex)>,
+    logout_command: String,
+    normalizer: OutputNormalizer,
+}
+
+fn compile(key: &str, pattern: &str) -> Result<Regex> {
+    Regex::new(pattern)
+        .map_err(|e| Error::ConfigValidation(format!("prompts.{}: некорректный шаблон: {}", key, e)))
+}
+
+impl CliProfile {
+    pub fn from_settings(settings: &DeviceSettings) -> Result<Self> {
+        let prompts = &settings.prompts;
+        let main = prompts.get("main").ok_or_else(|| Error::ConfigValidation(
+            "Для CLI драйвера необходимо указать device_settings.prompts.main".to_string()
+        ))?;
+        let username = prompts.get("username").or_else(|| prompts.get("login"))
+            .map_or(login::DEFAULT_USERNAME_PROMPT, String::as_str);
+        let password = prompts.get("password").map_or(login::DEFAULT_PASSWORD_PROMPT, String::as_str);
+
+        let mut error_keys: Vec<&String> = prompts.keys().filter(|key| key.starts_with("error")).collect();
+        error_keys.sort();
+        let errors = if error_keys.is_empty() {
+            DEFAULT_ERROR_MARKERS.iter()
+                .map(|pattern| Regex::new(pattern).expect("корректный шаблон"))
+                .collect()
+        } else {
+            error_keys.into_iter()
+                .map(|key| compile(key, &prompts[key]))
+                .collect::<Result<_>>()?
+        };
+
+        let modes = settings.extra_params.iter()
+            .filter_map(|(key, command)| key.strip_prefix("mode_").map(|mode| (mode.to_string(), command.clone())))
+            .collect();
+
+        let mode_prompts = DEFAULT_MODE_PROMPTS.iter()
+            .map(|&(name, default)| {
+                let key = format!("mode_{}", name);
+                let mode = match name {
+                    "sub_config" => CliMode::SubConfig(String::new()),
+                    "config" => CliMode::Config,
+                    "privileged" => CliMode::Privileged,
+                    _ => CliMode::User,
+                };
+                Ok((mode, compile(&key, prompts.get(&key).map_or(default, String::as_str))?))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            terminators: [
+                compile("main", &format!(r"(?:{})[ \t]*", main))?,
+                compile("username", username)?,
+                compile("password", password)?,
+                Regex::new(login::PASSWORD_CHANGE_PROMPT).expect("корректный шаблон"),
+            ],
+            errors,
+            modes,
+            mode_prompts,
+            logout_command: settings.extra_params.get("logout_command")
+                .cloned()
+                .unwrap_or_else(|| "exit".to_string()),
+            normalizer: OutputNormalizer::from_prompts(prompts)?,
+        })
+    }
+
+    /// Режим по промпту: первый совпавший из `mode_prompts`
+    fn mode_of(&self, prompt: &str) -> Option<&CliMode> {
+        self.mode_prompts.iter().find(|(_, pattern)| pattern.is_match(prompt)).map(|(mode, _)| mode)
+    }
+
+    /// Команда `extra_params.mode_<mode>`
+    fn mode_command(&self, mode: &str) -> Result<String> {
+        self.modes.get(mode).cloned().ok_or_else(|| Error::ConfigValidation(
+            format!("Не задана команда режима: extra_params.mode_{}", mode)
+        ))
+    }
+
+    /// Строка вывода, совпавшая с признаком ошибки
+    fn find_error<'a>(&self, output: &'a str) -> Option<&'a str> {
+        output.lines().find(|line| self.errors.iter().any(|marker| marker.is_match(line)))
+    }
+}
+
+/// Индексы в `CliProfile::terminators`
+const CLI: usize = 0;
+const USERNAME_PROMPT: usize = 1;
+const PASSWORD_PROMPT: usize = 2;
+const PASSWORD_CHANGE_PROMPT: usize = 3;
+
+/// Драйвер для любого CLI устройства, полностью описанного в конфигурации
+pub struct GenericCli<T: Transport> {
+    transport: T,
+    profile: CliProfile,
+    /// Какой промпт устройство показало последним
+    at: usize,
+    /// Режим по последнему промпту командной строки
+    mode: Option<CliMode>,
+    sub_config: SubConfigTracker,
+    enable_password: Option<String>,
+}
+
+impl<T: Transport> GenericCli<T> {
+    /// Создает драйвер на подключенном транспорте и дожидается промпта
+    /// (основного или приглашения входа)
+    pub fn new(mut transport: T, profile: CliProfile) -> Result<Self> {
+        transport.send(b"\r\n")?;
+        let timeout = transport.timeout();
+        let found = profile.normalizer.read_until_any(&mut transport, &profile.terminators, timeout)?;
+        let mut device = Self {
+            transport,
+            profile,
+            at: found.index,
+            mode: None,
+            sub_config: SubConfigTracker::default(),
+            enable_password: None,
+        };
+        device.observe(found.index, &found.matched);
+        Ok(device)
+    }
+
+    /// Запоминает режим по промпту командной строки
+    fn observe(&mut self, index: usize, matched: &str) {
+        if index != CLI {
+            return;
+        }
+        let prompt = matched.trim_end();
+        self.mode = self.profile.mode_of(prompt).cloned();
+        self.sub_config.observe(prompt, matches!(self.mode, Some(CliMode::SubConfig(_))));
+    }
+
+    /// Отправляет строку и читает вывод до промпта; возвращает индекс промпта и очищенный вывод
+    fn run(&mut self, line: &str) -> Result<(usize, String)> {
+        self.transport.send_string(line)?;
+        self.read(line)
+    }
+
+    /// Читает вывод до промпта; `command` - строка, эхо которой нужно убрать
+    fn read(&mut self, command: &str) -> Result<(usize, String)> {
+        let timeout = self.transport.timeout();
+        let found = self.profile.normalizer.read_until_any(&mut self.transport, &self.profile.terminators, timeout)?;
+        self.at = found.index;
+        self.observe(found.index, &found.matched);
+        Ok((found.index, self.profile.normalizer.clean(&found.before, command)))
+    }
+
+    /// Переходит в режим командой `extra_params.mode_<mode>`,
+    /// при запросе пароля отправляет `credentials.enable_password`
+    pub fn enter_mode(&mut self, mode: &str) -> Result<String> {
+        let command = self.profile.mode_command(mode)?;
+
+        let (index, output) = self.run(&command)?;
+        if index != PASSWORD_PROMPT {
+            return self.check(&command, output);
+        }
+        let password = self.enable_password.clone().ok_or_else(|| Error::ConfigValidation(
+            format!("Режим '{}' запрашивает пароль: укажите credentials.enable_password", mode)
+        ))?;
+        self.transport.send_string(&password)?;
+        let (_, output) = self.read("")?;
+        self.check(&command, output)
+    }
+
+    /// Выход из режима конфигурации командой `extra_params.mode_exit`
+    fn exit_mode(&mut self) -> Result<String> {
+        let command = self.profile.modes.get("exit").cloned().unwrap_or_else(|| "exit".to_string());
+        self.execute_command(&command)
+    }
+
+    fn check(&self, command: &str, output: String) -> Result<String> {
+        match self.profile.find_error(&output) {
+            Some(line) => Err(Error::Command(format!("{}: {}", command, line.trim()))),
+            None => Ok(output),
+        }
+    }
+}
+
+impl<T: Transport> LoginSteps for GenericCli<T> {
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            USERNAME_PROMPT => LoginPrompt::Username,
+            PASSWORD_PROMPT => LoginPrompt::Password,
+            PASSWORD_CHANGE_PROMPT => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        self.transport.send_string(line)?;
+        self.read("").map(|(_, output)| output)
+    }
+}
+
+impl<T: Transport> DeviceCommands for GenericCli<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        let (_, output) = self.run(command)?;
+        self.check(command, output)
+    }
+
+    /// Имя и пароль в ответ на промпты `username`/`password` из `device_settings.prompts`
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        self.enable_password = credentials.enable_password.clone();
+        login::login_sequence(self, "CLI", credentials)
+    }
+
+    fn cli_mode(&self) -> Option<CliMode> {
+        match &self.mode {
+            Some(CliMode::SubConfig(_)) => self.sub_config.mode(),
+            mode => mode.clone(),
+        }
+    }
+
+    /// Команды переходов из `extra_params.mode_*`
+    fn mode_transition(&mut self, to: &CliMode) -> Result<String> {
+        match (self.cli_mode(), to) {
+            (Some(CliMode::User), CliMode::Privileged) => self.enter_mode("privileged"),
+            (Some(CliMode::Privileged), CliMode::User) => self.enter_mode("user"),
+            (Some(CliMode::Privileged), CliMode::Config) => self.enter_mode("config"),
+            (Some(CliMode::Config), CliMode::Privileged) | (Some(CliMode::SubConfig(_)), CliMode::Config) => {
+                self.exit_mode()
+            }
+            (Some(CliMode::Config), CliMode::SubConfig(command)) => {
+                let output = self.execute_command(command)?;
+                self.sub_config.entered(command);
+                Ok(output)
+            }
+            (from, to) => Err(mode::no_transition("CLI", from, to)),
+        }
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        let command = self.profile.logout_command.clone();
+        self.transport.send_string(&command)?;
+
+        let patterns = [self.profile.terminators[USERNAME_PROMPT].clone(), self.profile.terminators[CLI].clone()];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout();
+        self.transport.disconnect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    fn settings(prompts: &[(&str, &str)], extra_params: &[(&str, &str)]) -> DeviceSettings {
+        let pairs = |items: &[(&str, &str)]| items.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        DeviceSettings {
+            prompts: pairs(prompts),
+            extra_params: pairs(extra_params),
+            init_commands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_login_mode_and_errors() {
+        let settings = settings(
+            &[("main", r"sw-\d+[>#]")],
+            &[("mode_privileged", "enable")],
+        );
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nUsername: ")
+            .expect_command("admin")
+            .respond("admin\r\nPassword: ")
+            .expect_command("pass")
+            .respond("\r\nsw-01>")
+            .expect_command("enable")
+            .respond("enable\r\nPassword: ")
+            .expect_command("secret")
+            .respond("\r\nsw-01#")
+            .expect_command("shw ver")
+            .respond("shw ver\r\n% Invalid input detected at '^' marker.\r\nsw-01#");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let mut device = GenericCli::new(mock, CliProfile::from_settings(&settings).unwrap()).unwrap();
+        let credentials = CredentialsConfig {
+            username: "admin".to_string(),
+            password: "pass".to_string(),
+            enable_password: Some("secret".to_string()),
+            ssh_key_path: None,
+        };
+        device.login(&credentials).unwrap();
+        assert_eq!(device.cli_mode(), Some(CliMode::User));
+        device.navigate_to(&CliMode::Privileged).unwrap();
+        assert_eq!(device.cli_mode(), Some(CliMode::Privileged));
+
+        match device.execute_command("shw ver") {
+            Err(Error::Command(message)) => assert!(message.contains("% Invalid input")),
+            other => panic!("ожидалась ошибка команды, получено {:?}", other),
+        }
+        assert!(matches!(device.enter_mode("config"), Err(Error::ConfigValidation(_))));
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_profile_requires_main_prompt() {
+        assert!(CliProfile::from_settings(&settings(&[], &[])).is_err());
+        assert!(CliProfile::from_settings(&settings(&[("main", "#"), ("error", "(")], &[])).is_err());
+    }
+}
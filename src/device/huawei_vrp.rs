@@ -0,0 +1,323 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::mode::{self, CliMode, SubConfigTracker};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::{Regex, Transport};
+use std::sync::LazyLock;
+
+/// Промпт VRP: `<HUAWEI>` (пользовательский вид), `[HUAWEI]`, `[HUAWEI-GigabitEthernet0/0/1]`,
+/// `[~HUAWEI]`/`[*HUAWEI]` на CE. Часть после имени начинается с `-`, поэтому `[Y/N]` не совпадает;
+/// только с начала строки, чтобы `<name>` или `[name]` внутри вывода не обрывали чтение
+pub(crate) const PROMPT: &str = r"(?m)^[<\[][~*]?[\w.\-]+(-[\w.\-/: ]+)?[>\]][ \t]*\z";
+
+const USERNAME_PROMPT: &str = r"(?i)username:[ \t]*$";
+
+const PASSWORD_PROMPT: &str = r"(?i)password:[ \t]*$";
+
+/// Предложение сменить пароль после входа: `The password needs to be changed. Change now? [Y/N]:`
+const CHANGE_PASSWORD_PROMPT: &str = r"(?i)change now\? ?\[y/n\]:?[ \t]*$";
+
+/// Запрос подтверждения: `Are you sure to continue? [Y/N]:`
+const CONFIRM_PROMPT: &str = r"(?i)\[y/n\]:?[ \t]*$";
+
+/// Запрос имени файла при `save`: `Please input the file name(*.cfg, *.zip)[vrpcfg.zip]:`
+const FILE_NAME_PROMPT: &str = r"(?i)file ?name[^\r\n]*:[ \t]*$";
+
+/// Сообщения VRP об ошибке команды
+const ERROR_MARKER: &str = r"(?i)^\s*(Error:|% ?(Unrecognized command|Incomplete command|Wrong parameter|Too many parameters))";
+
+/// Индексы в `TERMINATORS`
+const CLI: usize = 0;
+const USERNAME: usize = 1;
+const PASSWORD: usize = 2;
+const CHANGE_PASSWORD: usize = 3;
+const CONFIRM: usize = 4;
+const FILE_NAME: usize = 5;
+const PASSWORD_CHANGE: usize = 6;
+
+static ERROR: LazyLock<Regex> = LazyLock::new(|| Regex::new(ERROR_MARKER).expect("корректный шаблон"));
+
+/// Промпты, на которых завершается чтение вывода (индексы выше)
+static TERMINATORS: LazyLock<[Regex; 7]> = LazyLock::new(|| {
+    [
+        PROMPT, USERNAME_PROMPT, PASSWORD_PROMPT, CHANGE_PASSWORD_PROMPT, CONFIRM_PROMPT, FILE_NAME_PROMPT,
+        login::PASSWORD_CHANGE_PROMPT,
+    ]
+        .map(|pattern| Regex::new(pattern).expect("корректный шаблон"))
+});
+
+/// Драйвер коммутаторов и маршрутизаторов Huawei VRP (S, AR, CE, NE)
+pub struct HuaweiVrp<T: Transport> {
+    transport: T,
+    /// Какой промпт устройство показало последним
+    at: usize,
+    in_config: bool,
+    /// Имя из промпта пользовательского вида: `[имя]` - системный вид, остальное - вложенные
+    hostname: Option<String>,
+    sub_config: SubConfigTracker,
+    /// Ответ на запросы `[Y/N]`
+    confirm_answer: String,
+    normalizer: OutputNormalizer,
+}
+
+impl<T: Transport> HuaweiVrp<T> {
+    /// Создает драйвер на подключенном транспорте и дожидается промпта
+    /// (командной строки или приглашения входа)
+    pub fn new(mut transport: T) -> Result<Self> {
+        transport.send(b"\r\n")?;
+
+        let mut device = Self {
+            transport,
+            at: CLI,
+            in_config: false,
+            hostname: None,
+            sub_config: SubConfigTracker::default(),
+            confirm_answer: "Y".to_string(),
+            normalizer: OutputNormalizer::new(),
+        };
+        device.read("")?;
+        Ok(device)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Ответ на запросы `[Y/N]` (по умолчанию `Y` - подтверждать)
+    pub fn with_confirm_answer(mut self, answer: &str) -> Self {
+        self.confirm_answer = answer.to_string();
+        self
+    }
+
+    /// Находится ли CLI в системном виде или вложенном в него
+    pub fn in_config(&self) -> bool {
+        self.in_config
+    }
+
+    /// Читает вывод до промпта, отвечая на запросы подтверждения и имени файла
+    fn read(&mut self, command: &str) -> Result<String> {
+        let timeout = self.transport.timeout();
+        let mut raw = String::new();
+        loop {
+            let found = self.normalizer.read_until_any(&mut self.transport, &*TERMINATORS, timeout)?;
+            raw.push_str(&found.before);
+            self.at = found.index;
+
+            match found.index {
+                CONFIRM => {
+                    raw.push_str(&found.matched);
+                    let answer = self.confirm_answer.clone();
+                    self.transport.send_string(&answer)?;
+                }
+                FILE_NAME => {
+                    // Имя файла конфигурации по умолчанию
+                    raw.push_str(&found.matched);
+                    self.transport.send_string("")?;
+                }
+                CLI => {
+                    let prompt = found.matched.trim();
+                    let name = prompt[1..prompt.len() - 1].trim_start_matches(['~', '*']);
+                    self.in_config = prompt.starts_with('[');
+                    if self.in_config {
+                        let sub = self.hostname.as_deref().is_some_and(|hostname| name != hostname);
+                        self.sub_config.observe(name, sub);
+                    } else {
+                        self.hostname = Some(name.to_string());
+                    }
+                    break;
+                }
+                _ => break,
+            }
+        }
+        Ok(self.normalizer.clean(&raw, command))
+    }
+
+    fn send_secret(&mut self, secret: &str) -> Result<String> {
+        self.transport.send_string(secret)?;
+        self.read("")
+    }
+
+    fn check(command: &str, output: String) -> Result<String> {
+        match output.lines().find(|line| ERROR.is_match(line)) {
+            Some(line) => Err(Error::Command(format!("{}: {}", command, line.trim()))),
+            None => Ok(output),
+        }
+    }
+
+    /// Входит в системный вид (`system-view`)
+    pub fn system_view(&mut self) -> Result<String> {
+        if self.in_config {
+            return Ok(String::new());
+        }
+        let output = self.execute_command("system-view")?;
+        if !self.in_config {
+            return Err(Error::Command(format!("system-view: системный вид недоступен {}", output.trim())));
+        }
+        Ok(output)
+    }
+
+    /// Возвращается в пользовательский вид из любого вложенного (`return`)
+    pub fn return_to_user_view(&mut self) -> Result<String> {
+        if !self.in_config {
+            return Ok(String::new());
+        }
+        self.execute_command("return")
+    }
+
+    /// Сохраняет конфигурацию: `save` с подтверждением
+    pub fn save(&mut self) -> Result<String> {
+        self.return_to_user_view()?;
+        self.execute_command("save")
+    }
+}
+
+impl<T: Transport> LoginSteps for HuaweiVrp<T> {
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            USERNAME => LoginPrompt::Username,
+            PASSWORD => LoginPrompt::Password,
+            PASSWORD_CHANGE => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    /// От предложения сменить пароль VRP позволяет отказаться
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        let mut output = self.send_secret(line)?;
+        if self.at == CHANGE_PASSWORD {
+            output = self.send_secret("N")?;
+        }
+        Ok(output)
+    }
+}
+
+impl<T: Transport> DeviceCommands for HuaweiVrp<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        self.transport.send_string(command)?;
+        let output = self.read(command)?;
+        Self::check(command, output)
+    }
+
+    /// `Username:`/`Password:` (если спросят), отказ от смены пароля,
+    /// затем `screen-length 0 temporary`
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        login::login_sequence(self, "VRP", credentials)?;
+
+        self.execute_command("screen-length 0 temporary")
+    }
+
+    fn cli_mode(&self) -> Option<CliMode> {
+        Some(if self.in_config {
+            self.sub_config.mode().unwrap_or(CliMode::Config)
+        } else {
+            CliMode::Privileged
+        })
+    }
+
+    fn mode_transition(&mut self, to: &CliMode) -> Result<String> {
+        match (self.cli_mode(), to) {
+            (Some(CliMode::Privileged), CliMode::Config) => self.system_view(),
+            (Some(CliMode::Config), CliMode::Privileged) => self.return_to_user_view(),
+            (Some(CliMode::Config), CliMode::SubConfig(command)) => {
+                let output = self.execute_command(command)?;
+                self.sub_config.entered(command);
+                Ok(output)
+            }
+            (Some(CliMode::SubConfig(_)), CliMode::Config) => self.execute_command("quit"),
+            (from, to) => Err(mode::no_transition("VRP", from, to)),
+        }
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        if self.in_config {
+            self.return_to_user_view()?;
+        }
+        self.transport.send_string("quit")?;
+
+        let patterns = [Regex::new(r"(?i)press enter|username:").expect("корректный шаблон")];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout();
+        self.transport.disconnect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[test]
+    fn test_login_configure_and_save() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nLogin authentication\r\n\r\nUsername:")
+            .expect_command("admin")
+            .respond("admin\r\nPassword:")
+            .expect_command("Admin@123")
+            .respond("\r\nInfo: The password needs to be changed. Change now? [Y/N]: ")
+            .expect_command("N")
+            .respond("N\r\nInfo: The max number of VTY users is 5.\r\n<sw-core1>")
+            .expect_command("screen-length 0 temporary")
+            .respond("screen-length 0 temporary\r\nInfo: The configuration takes effect on the current user terminal interface only.\r\n<sw-core1>")
+            .expect_command("system-view")
+            .respond("system-view\r\nEnter system view, return user view with Ctrl+Z.\r\n[sw-core1]")
+            .expect_command("interface GigabitEthernet0/0/1")
+            .respond("interface GigabitEthernet0/0/1\r\n[sw-core1-GigabitEthernet0/0/1]")
+            .expect_command("descriptoin uplink")
+            .respond("descriptoin uplink\r\n              ^\r\nError: Unrecognized command found at '^' position.\r\n[sw-core1-GigabitEthernet0/0/1]")
+            .expect_command("return")
+            .respond("return\r\n<sw-core1>")
+            .expect_command("save")
+            .respond("save\r\nThe current configuration will be written to the device.\r\nAre you sure to continue? [Y/N]:")
+            .expect_command("Y")
+            .respond("Y\r\nNow saving the current configuration to the slot 0.\r\nSave the configuration successfully.\r\n<sw-core1>");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let credentials = CredentialsConfig {
+            username: "admin".to_string(),
+            password: "Admin@123".to_string(),
+            enable_password: None,
+            ssh_key_path: None,
+        };
+        let mut device = HuaweiVrp::new(mock).unwrap();
+        device.login(&credentials).unwrap();
+
+        device.system_view().unwrap();
+        device.execute_command("interface GigabitEthernet0/0/1").unwrap();
+        assert!(device.in_config());
+        assert!(matches!(device.execute_command("descriptoin uplink"), Err(Error::Command(_))));
+
+        let output = device.save().unwrap();
+        assert!(output.contains("Save the configuration successfully"));
+        assert!(!device.in_config());
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_prompt_only_at_line_start() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\n<sw-core1>")
+            .expect_command("display interface description")
+            .respond_chunks(&["display interface description\r\nGE0/0/1    up    up    to [core1]", " uplink\r\n<sw-core1>"]);
+        mock.connect().unwrap();
+
+        let mut device = HuaweiVrp::new(mock).unwrap();
+        let output = device.execute_command("display interface description").unwrap();
+        assert_eq!(output, "GE0/0/1    up    up    to [core1] uplink");
+    }
+}
@@ -0,0 +1,356 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::mode::{self, CliMode, SubConfigTracker};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::{Regex, Transport};
+use std::sync::LazyLock;
+
+/// Промпт CLI Junos: `admin@edge1> ` (операционный режим), `admin@edge1# ` (конфигурация);
+/// только с начала строки, чтобы `user@host>` внутри вывода не обрывал чтение
+pub(crate) const PROMPT: &str = r"(?m)^[\w.\-]+@[\w.\-]+[>#][ \t]*\z";
+
+/// Shell FreeBSD, куда попадает root: `root@edge1:RE:0% `, `root@edge1% `
+const SHELL_PROMPT: &str = r"\S+@[\w.\-:]+%[ \t]*$";
+
+const LOGIN_PROMPT: &str = r"(?i)login:[ \t]*$";
+
+const PASSWORD_PROMPT: &str = r"(?i)password:[ \t]*$";
+
+/// Вопрос при выходе с непримененными изменениями: `Discard uncommitted changes? [yes,no] (yes)`
+const QUESTION_PROMPT: &str = r"\[yes,no\] \(\w+\)[ \t]*$";
+
+/// Сообщения Junos об ошибке команды или commit
+const ERROR_MARKER: &str = r"(?i)^\s*(error:|syntax error|unknown command|missing argument|invalid (value|command|interface|ip address)|\S+ is ambiguous)";
+
+/// Индексы в `TERMINATORS`
+const CLI: usize = 0;
+const SHELL: usize = 1;
+const LOGIN: usize = 2;
+const PASSWORD: usize = 3;
+const QUESTION: usize = 4;
+const PASSWORD_CHANGE: usize = 5;
+
+static ERROR: LazyLock<Regex> = LazyLock::new(|| Regex::new(ERROR_MARKER).expect("корректный шаблон"));
+
+/// Промпты, на которых завершается чтение вывода (индексы выше)
+static TERMINATORS: LazyLock<[Regex; 6]> = LazyLock::new(|| {
+    [PROMPT, SHELL_PROMPT, LOGIN_PROMPT, PASSWORD_PROMPT, QUESTION_PROMPT, login::PASSWORD_CHANGE_PROMPT]
+        .map(|pattern| Regex::new(pattern).expect("корректный шаблон"))
+});
+
+/// Драйвер Juniper Junos (MX, SRX, EX, QFX).
+/// Изменения вносятся в приватную кандидат-конфигурацию (`configure private`);
+/// `commit confirmed N` откатывается сам, если за N минут не выполнить `commit`
+pub struct Junos<T: Transport> {
+    transport: T,
+    /// Какой промпт устройство показало последним
+    at: usize,
+    in_config: bool,
+    /// Уровень иерархии конфигурации по строке `[edit ...]` перед промптом
+    sub_config: SubConfigTracker,
+    normalizer: OutputNormalizer,
+}
+
+impl<T: Transport> Junos<T> {
+    /// Создает драйвер на подключенном транспорте и дожидается промпта
+    /// (командной строки или приглашения входа)
+    pub fn new(mut transport: T) -> Result<Self> {
+        transport.send(b"\r\n")?;
+
+        let mut device = Self {
+            transport,
+            at: CLI,
+            in_config: false,
+            sub_config: SubConfigTracker::default(),
+            normalizer: OutputNormalizer::new(),
+        };
+        device.read("")?;
+        Ok(device)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Находится ли CLI в режиме конфигурации
+    pub fn in_config(&self) -> bool {
+        self.in_config
+    }
+
+    fn read(&mut self, command: &str) -> Result<String> {
+        let timeout = self.transport.timeout();
+        let found = self.normalizer.read_until_any(&mut self.transport, &*TERMINATORS, timeout)?;
+
+        self.at = found.index;
+        if found.index == CLI {
+            self.in_config = found.matched.trim_end().ends_with('#');
+            let level = found.before.lines().rev().map(str::trim).find(|line| line.starts_with("[edit"));
+            if let Some(level) = level {
+                self.sub_config.observe(level, level != "[edit]");
+            }
+        }
+        Ok(self.normalizer.clean(&found.before, command))
+    }
+
+    fn send_secret(&mut self, secret: &str) -> Result<String> {
+        self.transport.send_string(secret)?;
+        self.read("")
+    }
+
+    fn check(command: &str, output: String) -> Result<String> {
+        match output.lines().find(|line| ERROR.is_match(line)) {
+            Some(line) => Err(Error::Command(format!("{}: {}", command, line.trim()))),
+            None => Ok(output),
+        }
+    }
+
+    /// Входит в приватную кандидат-конфигурацию (`configure private`)
+    pub fn configure(&mut self) -> Result<String> {
+        if self.in_config {
+            return Ok(String::new());
+        }
+        let output = self.execute_command("configure private")?;
+        if !self.in_config {
+            return Err(Error::Command(format!("configure private: режим конфигурации недоступен {}", output.trim())));
+        }
+        Ok(output)
+    }
+
+    /// Выходит из режима конфигурации; непримененные изменения отбрасываются
+    pub fn end_configure(&mut self) -> Result<String> {
+        if !self.in_config {
+            return Ok(String::new());
+        }
+        let mut output = self.execute_command("exit configuration-mode")?;
+        if self.at == QUESTION {
+            output = self.execute_command("yes")?;
+        }
+        Ok(output)
+    }
+
+    /// Проверяет кандидат-конфигурацию без применения
+    pub fn commit_check(&mut self) -> Result<String> {
+        self.execute_command("commit check")
+    }
+
+    /// Применяет конфигурацию с автоматическим откатом через `minutes` минут без подтверждения
+    pub fn commit_confirmed(&mut self, minutes: u32) -> Result<String> {
+        self.execute_command(&format!("commit confirmed {}", minutes))
+    }
+
+    /// Применяет конфигурацию; после `commit confirmed` подтверждает ее
+    pub fn commit(&mut self) -> Result<String> {
+        self.execute_command("commit")
+    }
+
+    /// Отменяет изменения кандидат-конфигурации (`rollback 0`)
+    /// или загружает одну из предыдущих конфигураций (`rollback N`)
+    pub fn rollback(&mut self, index: u32) -> Result<String> {
+        self.configure()?;
+        self.execute_command(&format!("rollback {}", index))
+    }
+
+    /// Выполняет команды в режиме конфигурации, затем `commit check`,
+    /// `commit confirmed N` и подтверждающий `commit`. При ошибке изменения отменяются
+    pub fn apply_config(&mut self, commands: &[&str], confirm_minutes: u32) -> Result<String> {
+        self.configure()?;
+
+        let mut output = Vec::new();
+        let result = commands.iter()
+            .try_for_each(|command| self.execute_command(command).map(|text| output.push(text)))
+            .and_then(|_| self.commit_check().map(|text| output.push(text)))
+            .and_then(|_| self.commit_confirmed(confirm_minutes).map(|text| output.push(text)));
+        if let Err(e) = result {
+            let _ = self.rollback(0);
+            let _ = self.end_configure();
+            return Err(e);
+        }
+        output.push(self.commit()?);
+        self.end_configure()?;
+
+        Ok(output.into_iter().filter(|text| !text.is_empty()).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+impl<T: Transport> LoginSteps for Junos<T> {
+    /// Shell root - тоже выполненный вход, `cli` запускается после
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            LOGIN => LoginPrompt::Username,
+            PASSWORD => LoginPrompt::Password,
+            PASSWORD_CHANGE => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        self.send_secret(line)
+    }
+}
+
+impl<T: Transport> DeviceCommands for Junos<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        self.transport.send_string(command)?;
+        let output = self.read(command)?;
+        Self::check(command, output)
+    }
+
+    /// `login:`/`Password:` (если спросят), `cli` из shell root, затем
+    /// `set cli screen-length 0` и отключение автодополнения по пробелу
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        login::login_sequence(self, "Junos", credentials)?;
+        if self.at == SHELL {
+            self.send_secret("cli")?;
+        }
+        if self.at != CLI {
+            return Err(Error::Connection("Junos: не удалось запустить cli из shell".to_string()));
+        }
+
+        self.execute_command("set cli complete-on-space off")?;
+        self.execute_command("set cli screen-length 0")
+    }
+
+    fn cli_mode(&self) -> Option<CliMode> {
+        Some(if self.in_config {
+            self.sub_config.mode().unwrap_or(CliMode::Config)
+        } else {
+            CliMode::Privileged
+        })
+    }
+
+    /// Вложенный режим - уровень иерархии: `SubConfig("edit interfaces ge-0/0/0")`
+    fn mode_transition(&mut self, to: &CliMode) -> Result<String> {
+        match (self.cli_mode(), to) {
+            (Some(CliMode::Privileged), CliMode::Config) => self.configure(),
+            (Some(CliMode::Config), CliMode::Privileged) => self.end_configure(),
+            (Some(CliMode::Config), CliMode::SubConfig(command)) => {
+                let output = self.execute_command(command)?;
+                self.sub_config.entered(command);
+                Ok(output)
+            }
+            (Some(CliMode::SubConfig(_)), CliMode::Config) => self.execute_command("top"),
+            (from, to) => Err(mode::no_transition("Junos", from, to)),
+        }
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        if self.in_config {
+            self.end_configure()?;
+        }
+        self.transport.send_string("exit")?;
+
+        // Консоль показывает login: (или shell root), SSH закрывает соединение
+        let patterns = [LOGIN_PROMPT, SHELL_PROMPT].map(|pattern| Regex::new(pattern).expect("корректный шаблон"));
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout();
+        self.transport.disconnect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    fn credentials() -> CredentialsConfig {
+        CredentialsConfig {
+            username: "root".to_string(),
+            password: "juniper1".to_string(),
+            enable_password: None,
+            ssh_key_path: None,
+        }
+    }
+
+    #[test]
+    fn test_console_login_and_apply_config() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\n\r\nAmnesiac (ttyu0)\r\n\r\nlogin: ")
+            .expect_command("root")
+            .respond("root\r\nPassword:")
+            .expect_command("juniper1")
+            .respond("\r\n\r\n--- JUNOS 21.4R3 Kernel 64-bit\r\nroot@edge1:RE:0% ")
+            .expect_command("cli")
+            .respond("cli\r\n{master:0}\r\nroot@edge1> ")
+            .expect_command("set cli complete-on-space off")
+            .respond("set cli complete-on-space off\r\nDisabling complete-on-space\r\n\r\nroot@edge1> ")
+            .expect_command("set cli screen-length 0")
+            .respond("set cli screen-length 0\r\nScreen length set to 0\r\n\r\nroot@edge1> ")
+            .expect_command("configure private")
+            .respond("configure private\r\nwarning: uncommitted changes will be discarded on exit\r\nEntering configuration mode\r\n\r\n[edit]\r\nroot@edge1# ")
+            .expect_command("set system host-name edge1")
+            .respond("set system host-name edge1\r\n\r\n[edit]\r\nroot@edge1# ")
+            .expect_command("commit check")
+            .respond("commit check\r\nconfiguration check succeeds\r\n\r\n[edit]\r\nroot@edge1# ")
+            .expect_command("commit confirmed 5")
+            .respond("commit confirmed 5\r\ncommit confirmed will be automatically rolled back in 5 minutes unless confirmed\r\ncommit complete\r\n\r\n[edit]\r\nroot@edge1# ")
+            .expect_command("commit")
+            .respond("commit\r\ncommit complete\r\n\r\n[edit]\r\nroot@edge1# ")
+            .expect_command("exit configuration-mode")
+            .respond("exit configuration-mode\r\nExiting configuration mode\r\n\r\nroot@edge1> ");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let mut device = Junos::new(mock).unwrap();
+        device.login(&credentials()).unwrap();
+
+        let output = device.apply_config(&["set system host-name edge1"], 5).unwrap();
+        assert!(output.contains("automatically rolled back in 5 minutes"));
+        assert!(!device.in_config());
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_prompt_only_at_line_start() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nadmin@edge1> ")
+            .expect_command("show configuration snmp")
+            .respond_chunks(&["show configuration snmp\r\ncontact \"noc@edge1>", "\";\r\n\r\nadmin@edge1> "]);
+        mock.connect().unwrap();
+
+        let mut device = Junos::new(mock).unwrap();
+        let output = device.execute_command("show configuration snmp").unwrap();
+        assert_eq!(output, "contact \"noc@edge1>\";");
+    }
+
+    #[test]
+    fn test_commit_error_rolls_back() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nadmin@edge1> ")
+            .expect_command("configure private")
+            .respond("configure private\r\nEntering configuration mode\r\n\r\n[edit]\r\nadmin@edge1# ")
+            .expect_command("delete interfaces lo0")
+            .respond("delete interfaces lo0\r\n\r\n[edit]\r\nadmin@edge1# ")
+            .expect_command("commit check")
+            .respond("commit check\r\n[edit protocols ospf area 0.0.0.0 interface lo0.0]\r\n  'interface lo0.0'\r\n    Interface must be configured\r\nerror: configuration check-out failed\r\n\r\n[edit]\r\nadmin@edge1# ")
+            .expect_command("rollback 0")
+            .respond("rollback 0\r\nload complete\r\n\r\n[edit]\r\nadmin@edge1# ")
+            .expect_command("exit configuration-mode")
+            .respond("exit configuration-mode\r\nExiting configuration mode\r\n\r\nadmin@edge1> ");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let mut device = Junos::new(mock).unwrap();
+        match device.apply_config(&["delete interfaces lo0"], 5) {
+            Err(Error::Command(message)) => assert!(message.contains("configuration check-out failed")),
+            other => panic!("ожидалась ошибка команды, получено {:?}", other),
+        }
+        assert!(!device.in_config());
+        handle.verify().unwrap();
+    }
+}
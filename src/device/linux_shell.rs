@@ -0,0 +1,244 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::{Regex, Transport};
+
+const LOGIN_PROMPT: &str = r"(?i)login:[ \t]*$";
+
+const PASSWORD_PROMPT: &str = r"(?i)password( for [^:]+)?:[ \t]*$";
+
+/// Промпт shell до установки собственного (`user@host:~$`, `#`, `%`)
+const SHELL_PROMPT: &str = r"[$#%>][ \t]*$";
+
+/// Собственный промпт с кодом завершения последней команды: `RACKIT[0]> `
+const RACKIT_PROMPT: &str = r"RACKIT\[(\d+)\]> $";
+
+/// Устанавливает промпт с кодом завершения и отключает пейджеры и цвета.
+/// Кавычки разрывают текст промпта, чтобы эхо этой команды не совпало с ним
+const SETUP_COMMAND: &str =
+    "unset PROMPT_COMMAND; export PAGER=cat SYSTEMD_PAGER=cat TERM=dumb; PS2=''; PS1='RACKIT[$?]''> '";
+
+/// Индексы в `LinuxShell::terminators`
+const SHELL: usize = 0;
+const LOGIN: usize = 1;
+const PASSWORD: usize = 2;
+const PASSWORD_CHANGE: usize = 3;
+
+/// Драйвер shell Linux (консоль или SSH): вход, собственный промпт и коды завершения.
+/// Команда с ненулевым кодом завершения считается ошибкой шага
+pub struct LinuxShell<T: Transport> {
+    transport: T,
+    /// Промпт shell (исходный, пока не установлен собственный) и приглашения входа
+    terminators: [Regex; 4],
+    prompt_ready: bool,
+    /// Какой промпт пришел последним (SHELL, LOGIN или PASSWORD)
+    at: usize,
+    /// Пароль для ответа sudo
+    password: Option<String>,
+    normalizer: OutputNormalizer,
+}
+
+impl<T: Transport> LinuxShell<T> {
+    /// Создает драйвер на подключенном транспорте; если shell уже доступен
+    /// (SSH или открытая консоль), сразу устанавливает собственный промпт
+    pub fn new(mut transport: T) -> Result<Self> {
+        transport.send(b"\r\n")?;
+
+        let mut shell = Self {
+            transport,
+            terminators: [SHELL_PROMPT, LOGIN_PROMPT, PASSWORD_PROMPT, login::PASSWORD_CHANGE_PROMPT]
+                .map(|pattern| Regex::new(pattern).expect("корректный шаблон")),
+            prompt_ready: false,
+            at: SHELL,
+            password: None,
+            normalizer: OutputNormalizer::new(),
+        };
+        shell.read("")?;
+        if shell.at == SHELL {
+            shell.setup_prompt()?;
+        }
+        Ok(shell)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// Читает вывод до промпта; для собственного промпта возвращает и код завершения
+    fn read(&mut self, command: &str) -> Result<(String, Option<i32>)> {
+        let timeout = self.transport.timeout();
+        let found = self.normalizer.read_until_any(&mut self.transport, &self.terminators, timeout)?;
+
+        self.at = found.index;
+        let status = if found.index == SHELL && self.prompt_ready {
+            self.terminators[SHELL].captures(&found.matched).and_then(|caps| caps[1].parse().ok())
+        } else {
+            None
+        };
+        Ok((self.normalizer.clean(&found.before, command), status))
+    }
+
+    fn send_secret(&mut self, secret: &str) -> Result<(String, Option<i32>)> {
+        self.transport.send_string(secret)?;
+        self.read("")
+    }
+
+    fn setup_prompt(&mut self) -> Result<()> {
+        self.transport.send_string(SETUP_COMMAND)?;
+        self.terminators[SHELL] = Regex::new(RACKIT_PROMPT).expect("корректный шаблон");
+        self.prompt_ready = true;
+        self.read(SETUP_COMMAND)?;
+        if self.at != SHELL {
+            return Err(Error::Connection("Linux: не удалось установить промпт shell".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Выполняет команду и возвращает вывод вместе с кодом завершения
+    pub fn run(&mut self, command: &str) -> Result<(String, i32)> {
+        if !self.prompt_ready {
+            return Err(Error::Connection("Linux: вход в систему не выполнен".to_string()));
+        }
+
+        self.transport.send_string(command)?;
+        let (mut output, mut status) = self.read(command)?;
+        if self.at == PASSWORD {
+            // sudo и подобные: отвечаем паролем входа
+            let password = self.password.clone().ok_or_else(|| Error::Command(
+                format!("{}: команда запросила пароль", command)
+            ))?;
+            let (rest, rest_status) = self.send_secret(&password)?;
+            output = rest;
+            status = rest_status;
+        }
+
+        match status {
+            Some(status) => Ok((output, status)),
+            None => Err(Error::Command(format!("{}: shell не вернул промпт {}", command, output.trim()))),
+        }
+    }
+}
+
+impl<T: Transport> LoginSteps for LinuxShell<T> {
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            LOGIN => LoginPrompt::Username,
+            PASSWORD => LoginPrompt::Password,
+            PASSWORD_CHANGE => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        self.send_secret(line).map(|(output, _)| output)
+    }
+}
+
+impl<T: Transport> DeviceCommands for LinuxShell<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        let (output, status) = self.run(command)?;
+        if status != 0 {
+            let last_line = output.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("");
+            return Err(Error::Command(format!("{}: код завершения {} {}", command, status, last_line.trim())));
+        }
+        Ok(output)
+    }
+
+    /// `login:`/`Password:` (если спросят), затем установка собственного промпта
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        self.password = Some(credentials.password.clone());
+        if self.prompt_ready {
+            return Ok(String::new());
+        }
+
+        let output = login::login_sequence(self, "Linux", credentials)?;
+        self.setup_prompt()?;
+        Ok(output)
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        self.transport.send_string("exit")?;
+
+        // Консоль показывает login:, SSH закрывает соединение
+        let patterns = [Regex::new(LOGIN_PROMPT).expect("корректный шаблон")];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout();
+        self.transport.disconnect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    fn credentials() -> CredentialsConfig {
+        CredentialsConfig {
+            username: "ubuntu".to_string(),
+            password: "secret".to_string(),
+            enable_password: None,
+            ssh_key_path: None,
+        }
+    }
+
+    #[test]
+    fn test_console_login_and_exit_status() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\r\nUbuntu 22.04 LTS srv ttyS0\r\n\r\nsrv login: ")
+            .expect_command("ubuntu")
+            .respond("ubuntu\r\nPassword: ")
+            .expect_command("secret")
+            .respond("\r\nLast login: Mon Jan  1 00:00:00 2024\r\nubuntu@srv:~$ ")
+            .expect_command(SETUP_COMMAND)
+            .respond(&format!("{}\r\nRACKIT[0]> ", SETUP_COMMAND))
+            .expect_command("uname -s")
+            .respond("uname -s\r\nLinux\r\nRACKIT[0]> ")
+            .expect_command("sudo systemctl restart nginx")
+            .respond("sudo systemctl restart nginx\r\n[sudo] password for ubuntu: ")
+            .expect_command("secret")
+            .respond("\r\nJob for nginx.service failed.\r\nRACKIT[1]> ")
+            .expect_command("exit")
+            .respond("logout\r\n\r\nsrv login: ");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let mut shell = LinuxShell::new(mock).unwrap();
+        shell.login(&credentials()).unwrap();
+        assert_eq!(shell.execute_command("uname -s").unwrap(), "Linux");
+
+        match shell.execute_command("sudo systemctl restart nginx") {
+            Err(Error::Command(message)) => assert!(message.contains("код завершения 1")),
+            other => panic!("ожидалась ошибка команды, получено {:?}", other),
+        }
+
+        shell.disconnect().unwrap();
+        handle.verify().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_real_shell_over_pty() {
+        let mut transport = crate::transport::PtyTransport::new("exec sh -i".to_string());
+        transport.connect().unwrap();
+        transport.set_timeout(std::time::Duration::from_secs(5)).unwrap();
+
+        let mut shell = LinuxShell::new(transport).unwrap();
+        assert_eq!(shell.run("echo hello; false").unwrap(), ("hello".to_string(), 1));
+        assert_eq!(shell.execute_command("printf 'a\\nb\\n'").unwrap(), "a\nb");
+        shell.disconnect().unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::error::types::{Error, Result};
+use crate::transport::Regex;
+use std::sync::LazyLock;
+
+/// Приглашение ввода имени пользователя, если драйвер не знает своего
+pub(crate) const DEFAULT_USERNAME_PROMPT: &str = r"(?i)(login|username|user name):[ \t]*$";
+
+/// Приглашение ввода пароля, если драйвер не знает своего
+pub(crate) const DEFAULT_PASSWORD_PROMPT: &str = r"(?i)password:[ \t]*$";
+
+/// Требование сменить пароль при входе: `New password:`, `Your password has expired`,
+/// `Current password:` (Linux спрашивает текущий пароль перед сменой), `new password>` (RouterOS)
+pub(crate) const PASSWORD_CHANGE_PROMPT: &str =
+    r"(?i)(new password|current password|\(current\) unix password|(change|update) (your|the) password|password (has )?expired)[^\r\n]*[:?>][ \t]*$";
+
+/// Сообщения устройства о неудачном входе
+const FAILURE_MARKER: &str =
+    r"(?i)(login incorrect|login invalid|authentication fail|access denied|permission denied|bad (password|user)|(invalid|wrong|incorrect) (password|user|login|credentials))";
+
+static FAILURE: LazyLock<Regex> = LazyLock::new(|| Regex::new(FAILURE_MARKER).expect("корректный шаблон"));
+
+/// Приглашение, которое устройство показало последним
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoginPrompt {
+    /// Командная строка: вход выполнен (или уже был выполнен)
+    Cli,
+    Username,
+    Password,
+    /// Устройство требует сменить пароль
+    PasswordChange,
+}
+
+/// Шаги входа, которые драйвер выполняет своими средствами:
+/// драйвер знает свои промпты и отвечает на свои вопросы (лицензия, `cli` из shell и т.п.)
+pub(crate) trait LoginSteps {
+    /// Какое приглашение устройство показало последним
+    fn login_prompt(&self) -> LoginPrompt;
+
+    /// Отправляет имя пользователя или пароль и читает вывод до следующего приглашения
+    fn send_login_line(&mut self, line: &str) -> Result<String>;
+}
+
+/// Вход по приглашениям: имя и пароль отправляются только в ответ на свои приглашения,
+/// уже открытая командная строка считается выполненным входом. Повторное приглашение
+/// после отправки, требование сменить пароль и сообщения об отказе дают `Error::Authentication`.
+/// Возвращает вывод входа (баннеры, сообщения дня)
+pub(crate) fn login_sequence<D: LoginSteps + ?Sized>(
+    device: &mut D,
+    driver: &str,
+    credentials: &CredentialsConfig,
+) -> Result<String> {
+    let mut state = LoginState::new(driver, credentials);
+    while let Some(line) = state.next_line(device.login_prompt())? {
+        let text = device.send_login_line(line)?;
+        state.push_output(&text);
+    }
+    Ok(state.into_output())
+}
+
+/// Ход входа по приглашениям (`login_sequence`); асинхронные драйверы
+/// ведут вход по нему же, отправляя строки своими средствами
+pub(crate) struct LoginState<'a> {
+    driver: &'a str,
+    credentials: &'a CredentialsConfig,
+    output: String,
+    sent_username: bool,
+    sent_password: bool,
+}
+
+impl<'a> LoginState<'a> {
+    pub(crate) fn new(driver: &'a str, credentials: &'a CredentialsConfig) -> Self {
+        Self { driver, credentials, output: String::new(), sent_username: false, sent_password: false }
+    }
+
+    /// Строка в ответ на приглашение `prompt`; `None` - вход выполнен
+    pub(crate) fn next_line(&mut self, prompt: LoginPrompt) -> Result<Option<&'a str>> {
+        let credentials = self.credentials;
+        match prompt {
+            LoginPrompt::Cli => Ok(None),
+            LoginPrompt::Username if !self.sent_username => {
+                self.sent_username = true;
+                Ok(Some(&credentials.username))
+            }
+            LoginPrompt::Password if !self.sent_password => {
+                self.sent_password = true;
+                Ok(Some(&credentials.password))
+            }
+            LoginPrompt::PasswordChange => Err(Error::Authentication(format!(
+                "{}: пользователь '{}' должен сменить пароль", self.driver, credentials.username
+            ))),
+            _ => Err(failure(self.driver, &credentials.username, &self.output)),
+        }
+    }
+
+    /// Добавляет вывод устройства после отправленной строки
+    pub(crate) fn push_output(&mut self, text: &str) {
+        if !text.is_empty() {
+            if !self.output.is_empty() {
+                self.output.push('\n');
+            }
+            self.output.push_str(text);
+        }
+    }
+
+    pub(crate) fn into_output(self) -> String {
+        self.output
+    }
+}
+
+/// Ошибка входа с сообщением устройства, если оно есть в выводе
+fn failure(driver: &str, username: &str, output: &str) -> Error {
+    let reason = output.lines()
+        .find(|line| FAILURE.is_match(line))
+        .map(str::trim)
+        .unwrap_or("неверное имя пользователя или пароль");
+    Error::Authentication(format!("{}: вход как '{}' не выполнен ({})", driver, username, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Устройство по сценарию: каждая отправленная строка получает следующий ответ
+    struct Scripted {
+        prompt: LoginPrompt,
+        replies: Vec<(LoginPrompt, &'static str)>,
+        sent: Vec<String>,
+    }
+
+    impl LoginSteps for Scripted {
+        fn login_prompt(&self) -> LoginPrompt {
+            self.prompt
+        }
+
+        fn send_login_line(&mut self, line: &str) -> Result<String> {
+            self.sent.push(line.to_string());
+            let (prompt, text) = self.replies.remove(0);
+            self.prompt = prompt;
+            Ok(text.to_string())
+        }
+    }
+
+    fn credentials() -> CredentialsConfig {
+        CredentialsConfig {
+            username: "admin".to_string(),
+            password: "secret".to_string(),
+            enable_password: None,
+            ssh_key_path: None,
+        }
+    }
+
+    fn device(prompt: LoginPrompt, replies: Vec<(LoginPrompt, &'static str)>) -> Scripted {
+        Scripted { prompt, replies, sent: Vec::new() }
+    }
+
+    #[test]
+    fn test_login_sequence() {
+        let mut logged_in = device(LoginPrompt::Cli, Vec::new());
+        assert_eq!(login_sequence(&mut logged_in, "Test", &credentials()).unwrap(), "");
+        assert!(logged_in.sent.is_empty());
+
+        let mut console = device(LoginPrompt::Username, vec![
+            (LoginPrompt::Password, ""),
+            (LoginPrompt::Cli, "Authorized access only"),
+        ]);
+        assert_eq!(login_sequence(&mut console, "Test", &credentials()).unwrap(), "Authorized access only");
+        assert_eq!(console.sent, ["admin", "secret"]);
+    }
+
+    #[test]
+    fn test_login_failures() {
+        let mut rejected = device(LoginPrompt::Username, vec![
+            (LoginPrompt::Password, ""),
+            (LoginPrompt::Username, "% Login invalid"),
+        ]);
+        match login_sequence(&mut rejected, "Test", &credentials()) {
+            Err(Error::Authentication(message)) => assert!(message.contains("% Login invalid")),
+            other => panic!("ожидалась ошибка входа, получено {:?}", other),
+        }
+
+        let mut expired = device(LoginPrompt::Password, vec![
+            (LoginPrompt::PasswordChange, "Your password has expired"),
+        ]);
+        assert!(matches!(login_sequence(&mut expired, "Test", &credentials()), Err(Error::Authentication(_))));
+    }
+}
@@ -0,0 +1,382 @@
+use crate::config_engine::types::CredentialsConfig;
+use crate::device::commands::DeviceCommands;
+use crate::device::login::{self, LoginPrompt, LoginSteps};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::{Regex, Transport};
+use std::sync::LazyLock;
+use std::collections::HashMap;
+
+/// Промпт RouterOS: `[admin@MikroTik] > `, `[admin@MikroTik] /ip address> `; только с начала строки,
+/// чтобы `[user@host] >` внутри вывода не обрывал чтение
+pub(crate) const PROMPT: &str = r"(?m)^\[[^\]\r\n@]+@[^\]\r\n]+\] ?[^\r\n>\[]*>[ \t]*\z";
+
+const USERNAME_PROMPT: &str = r"(?i)login:[ \t]*$";
+
+const PASSWORD_PROMPT: &str = r"(?i)password:[ \t]*$";
+
+/// Вопрос после первого входа: `Do you want to see the software license? [Y/n]:`
+const QUESTION_PROMPT: &str = r"\[[Yy]/[Nn]\]:?[ \t]*$";
+
+/// Суффикс имени пользователя: без цветов (c), без автоопределения терминала (t),
+/// широкий и высокий экран без переносов и постраничного вывода
+pub const LOGIN_SUFFIX: &str = "+ct511w4098h";
+
+/// Пейджер RouterOS; `D` выводит остаток без остановок
+const PAGER: &str = r"-- \[Q quit\|D dump\|(down|C-z pause)[^\]]*\]";
+
+/// Сообщения RouterOS об ошибке команды
+const ERROR_MARKER: &str = r"(?i)^\s*(bad command name|syntax error|expected (end of command|command name)|failure:|no such item|invalid value|input does not match|ambiguous value|missing value|value of \S+ out of range)";
+
+/// Индексы в `TERMINATORS`
+const CLI: usize = 0;
+const USERNAME: usize = 1;
+const PASSWORD: usize = 2;
+const QUESTION: usize = 3;
+const PASSWORD_CHANGE: usize = 4;
+
+static ERROR: LazyLock<Regex> = LazyLock::new(|| Regex::new(ERROR_MARKER).expect("корректный шаблон"));
+
+/// Промпты, на которых завершается чтение вывода (индексы выше)
+static TERMINATORS: LazyLock<[Regex; 5]> = LazyLock::new(|| {
+    [PROMPT, USERNAME_PROMPT, PASSWORD_PROMPT, QUESTION_PROMPT, login::PASSWORD_CHANGE_PROMPT]
+        .map(|pattern| Regex::new(pattern).expect("корректный шаблон"))
+});
+
+/// Имя для входа с `LOGIN_SUFFIX`, если суффикс не указан явно (`admin+ct`)
+pub fn login_name(username: &str) -> String {
+    if username.contains('+') {
+        username.to_string()
+    } else {
+        format!("{}{}", username, LOGIN_SUFFIX)
+    }
+}
+
+/// Нормализатор с пейджером RouterOS; ключи `pager*` из `prompts` имеют приоритет
+pub fn normalizer(prompts: &HashMap<String, String>) -> Result<OutputNormalizer> {
+    let mut merged = HashMap::from([
+        ("pager".to_string(), PAGER.to_string()),
+        ("pager_answer".to_string(), "D".to_string()),
+    ]);
+    if prompts.keys().any(|key| key.starts_with("pager")) {
+        merged.clear();
+    }
+    merged.extend(prompts.iter().map(|(key, value)| (key.clone(), value.clone())));
+    OutputNormalizer::from_prompts(&merged)
+}
+
+/// Драйвер MikroTik RouterOS (консоль или SSH).
+/// Через SSH суффикс входа нужно указать в `credentials.username` (`admin+ct`):
+/// имя пользователя SSH передается до создания драйвера
+pub struct RouterOs<T: Transport> {
+    transport: T,
+    at: usize,
+    normalizer: OutputNormalizer,
+}
+
+impl<T: Transport> RouterOs<T> {
+    /// Создает драйвер на подключенном транспорте и дожидается промпта
+    /// (командной строки или приглашения входа)
+    pub fn new(mut transport: T) -> Result<Self> {
+        transport.send(b"\r")?;
+
+        let mut device = Self {
+            transport,
+            at: CLI,
+            normalizer: normalizer(&HashMap::new())?,
+        };
+        device.read("")?;
+        Ok(device)
+    }
+
+    /// Обработка вывода с настройками устройства (пейджер и т.п.)
+    pub fn with_normalizer(mut self, normalizer: OutputNormalizer) -> Self {
+        self.normalizer = normalizer;
+        self
+    }
+
+    /// RouterOS ждет одиночный \r: \r\n выводит лишний промпт
+    fn send_line(&mut self, line: &str) -> Result<()> {
+        self.transport.send(format!("{}\r", line).as_bytes())
+    }
+
+    fn read(&mut self, command: &str) -> Result<String> {
+        let timeout = self.transport.timeout();
+        let found = self.normalizer.read_until_any(&mut self.transport, &*TERMINATORS, timeout)?;
+        self.at = found.index;
+        Ok(self.normalizer.clean(&found.before, command))
+    }
+
+    fn check(command: &str, output: String) -> Result<String> {
+        match output.lines().find(|line| ERROR.is_match(line)) {
+            Some(line) => Err(Error::Command(format!("{}: {}", command, line.trim()))),
+            None => Ok(output),
+        }
+    }
+
+    /// Значение выражения через `:put`, например `put("[/system identity get name]")`
+    pub fn put(&mut self, expression: &str) -> Result<String> {
+        let output = self.execute_command(&format!(":put {}", expression))?;
+        Ok(output.trim().to_string())
+    }
+
+    /// `export terse` раздела (`Some("/ip address")`) или всей конфигурации, разобранный по командам
+    pub fn export(&mut self, path: Option<&str>) -> Result<Vec<ExportEntry>> {
+        let command = match path {
+            Some(path) => format!("{} export terse", path),
+            None => "/export terse".to_string(),
+        };
+        let output = self.execute_command(&command)?;
+        Ok(parse_export(&output))
+    }
+}
+
+impl<T: Transport> LoginSteps for RouterOs<T> {
+    fn login_prompt(&self) -> LoginPrompt {
+        match self.at {
+            USERNAME => LoginPrompt::Username,
+            PASSWORD => LoginPrompt::Password,
+            PASSWORD_CHANGE => LoginPrompt::PasswordChange,
+            _ => LoginPrompt::Cli,
+        }
+    }
+
+    /// Вопрос о просмотре лицензии после входа получает ответ `n` (одной клавишей)
+    fn send_login_line(&mut self, line: &str) -> Result<String> {
+        self.send_line(line)?;
+        let mut output = self.read("")?;
+        if self.at == QUESTION {
+            self.transport.send(b"n")?;
+            output = self.read("")?;
+        }
+        Ok(output)
+    }
+}
+
+impl<T: Transport> DeviceCommands for RouterOs<T> {
+    fn execute_command(&mut self, command: &str) -> Result<String> {
+        self.send_line(command)?;
+        let output = self.read(command)?;
+        Self::check(command, output)
+    }
+
+    /// `Login:`/`Password:` (если спросят) с суффиксом `LOGIN_SUFFIX`;
+    /// от просмотра лицензии после первого входа отказывается
+    fn login(&mut self, credentials: &CredentialsConfig) -> Result<String> {
+        let credentials = CredentialsConfig {
+            username: login_name(&credentials.username),
+            ..credentials.clone()
+        };
+        login::login_sequence(self, "RouterOS", &credentials)
+    }
+
+    fn logout(&mut self) -> Result<String> {
+        self.send_line("/quit")?;
+
+        let patterns = [Regex::new(USERNAME_PROMPT).expect("корректный шаблон")];
+        let timeout = self.transport.timeout();
+        match self.transport.expect(&patterns, timeout) {
+            Ok(found) => Ok(found.text()),
+            Err(Error::Timeout(output)) => Ok(output),
+            Err(Error::Connection(_)) => Ok(String::new()),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        let _ = self.logout();
+        self.transport.disconnect()
+    }
+}
+
+/// Команда из вывода `export`: `/ip address add address=10.0.0.1/24 interface=ether1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportEntry {
+    /// Раздел меню: `/ip address`
+    pub path: String,
+    /// Действие: `add`, `set`, `remove` и т.п.
+    pub command: String,
+    /// Выбор элемента: `[ find default-name=ether1 ]`
+    pub selector: Option<String>,
+    /// Параметры в порядке вывода; у параметров без `=` значение пустое
+    pub params: Vec<(String, String)>,
+}
+
+impl ExportEntry {
+    /// Значение параметра
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Делит строку на слова с учетом кавычек (`comment="a b"`) и скобок (`[ find ... ]`)
+fn tokenize(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut depth = 0usize;
+    let mut chars = line.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if quoted => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            '"' => quoted = !quoted,
+            '[' if !quoted => {
+                depth += 1;
+                current.push(ch);
+            }
+            ']' if !quoted && depth > 0 => {
+                depth -= 1;
+                current.push(ch);
+            }
+            ch if ch.is_whitespace() && !quoted && depth == 0 => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(ch),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Разбирает вывод `export` (обычный или `terse`): склеивает переносы `\`,
+/// пропускает комментарии и раскладывает команды по разделам
+pub fn parse_export(text: &str) -> Vec<ExportEntry> {
+    const COMMANDS: &[&str] = &["add", "set", "remove", "enable", "disable", "unset", "move"];
+
+    let joined = text.replace("\\\r\n", "").replace("\\\n", "");
+    let mut section = String::new();
+    let mut entries = Vec::new();
+
+    for line in joined.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut tokens = tokenize(line);
+        if line.starts_with('/') {
+            let command_index = tokens.iter().position(|token| COMMANDS.contains(&token.as_str()));
+            match command_index {
+                Some(index) => {
+                    section = tokens[..index].join(" ");
+                    tokens.drain(..index);
+                }
+                None => {
+                    // Заголовок раздела без команды
+                    section = tokens.join(" ");
+                    continue;
+                }
+            }
+        }
+
+        let mut tokens = tokens.into_iter();
+        let command = match tokens.next() {
+            Some(command) => command,
+            None => continue,
+        };
+        let mut entry = ExportEntry {
+            path: section.clone(),
+            command,
+            selector: None,
+            params: Vec::new(),
+        };
+        for token in tokens {
+            if token.starts_with('[') {
+                entry.selector = Some(token);
+            } else if let Some((key, value)) = token.split_once('=') {
+                entry.params.push((key.to_string(), value.to_string()));
+            } else {
+                entry.params.push((token, String::new()));
+            }
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[test]
+    fn test_console_login_and_put() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r")
+            .respond("\r\n\r\nMikroTik 7.12 (stable)\r\nMikroTik Login: ")
+            .expect_bytes("admin+ct511w4098h\r")
+            .respond("admin+ct511w4098h\r\nPassword: ")
+            .expect_bytes("secret\r")
+            .respond("\r\n\r\n  MMM      MMM       KKK\r\n\r\nDo you want to see the software license? [Y/n]: ")
+            .expect_bytes("n")
+            .respond("\r\n[admin@branch-1] > ")
+            .expect_bytes(":put [/system identity get name]\r")
+            .respond(":put [/system identity get name]\r\nbranch-1\r\n[admin@branch-1] > ")
+            .expect_bytes("/ip route add gateway=\r")
+            .respond("/ip route add gateway=\r\nexpected end of command (line 1 column 15)\r\n[admin@branch-1] > ");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let credentials = CredentialsConfig {
+            username: "admin".to_string(),
+            password: "secret".to_string(),
+            enable_password: None,
+            ssh_key_path: None,
+        };
+        let mut device = RouterOs::new(mock).unwrap();
+        device.login(&credentials).unwrap();
+
+        assert_eq!(device.put("[/system identity get name]").unwrap(), "branch-1");
+        assert!(matches!(device.execute_command("/ip route add gateway="), Err(Error::Command(_))));
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_prompt_only_at_line_start() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r")
+            .respond("\r\n[admin@branch-1] > ")
+            .expect_bytes("/interface print terse\r")
+            .respond_chunks(&["/interface print terse\r\n 0 R name=ether1 comment=via [ops@core] >", " uplink\r\n[admin@branch-1] > "]);
+        mock.connect().unwrap();
+
+        let mut device = RouterOs::new(mock).unwrap();
+        let output = device.execute_command("/interface print terse").unwrap();
+        assert_eq!(output, " 0 R name=ether1 comment=via [ops@core] > uplink");
+    }
+
+    #[test]
+    fn test_parse_export() {
+        let export = concat!(
+            "# 2024-01-01 00:00:00 by RouterOS 7.12\r\n",
+            "# software id = ABCD-1234\r\n",
+            "/interface ethernet\r\n",
+            "set [ find default-name=ether1 ] comment=\"WAN link\" name=wan\r\n",
+            "/ip address\r\n",
+            "add address=192.168.88.1/24 interface=bridge \\\r\n",
+            "    network=192.168.88.0\r\n",
+            "/system identity set name=branch-1\r\n",
+        );
+
+        let entries = parse_export(export);
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].path, "/interface ethernet");
+        assert_eq!(entries[0].selector.as_deref(), Some("[ find default-name=ether1 ]"));
+        assert_eq!(entries[0].get("comment"), Some("WAN link"));
+
+        assert_eq!(entries[1].command, "add");
+        assert_eq!(entries[1].get("network"), Some("192.168.88.0"));
+
+        assert_eq!(entries[2].path, "/system identity");
+        assert_eq!(entries[2].get("name"), Some("branch-1"));
+    }
+}
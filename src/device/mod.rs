@@ -1,6 +1,31 @@
 pub mod commands;
-pub mod eltex_esr200;
+pub mod eltex_esr;
+pub mod eltex_mes;
+pub mod cisco_ios;
+pub mod huawei_vrp;
+pub mod juniper_junos;
+pub mod linux_shell;
+pub mod login;
+pub mod mikrotik_routeros;
+pub mod mode;
+pub mod terminal;
+pub mod generic_cli;
+pub mod registry;
+#[cfg(feature = "async")]
+pub mod async_cli;
 
 pub use commands::DeviceCommands;
-pub use eltex_esr200::EltexEsr200;
+pub use eltex_esr::EltexEsr;
+pub use eltex_mes::EltexMes;
+pub use cisco_ios::{CiscoIos, IosMode};
+pub use huawei_vrp::HuaweiVrp;
+pub use juniper_junos::Junos;
+pub use linux_shell::LinuxShell;
+pub use mikrotik_routeros::RouterOs;
+pub use mode::{mode_path, CliMode};
+pub use terminal::OutputNormalizer;
+pub use generic_cli::{CliProfile, GenericCli};
+pub use registry::{DriverFactory, DriverRegistry, PromptPattern, GENERIC_DRIVER};
+#[cfg(feature = "async")]
+pub use async_cli::{AsyncCliDevice, AsyncDeviceCommands};
 
@@ -0,0 +1,164 @@
+use crate::error::types::Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Режим командной строки устройства. Режимы образуют цепочку
+/// `User` → `Privileged` → `Config` → `SubConfig`; драйвер умеет переходить
+/// только в соседний режим, путь между любыми двумя строит `mode_path`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CliMode {
+    /// Пользовательский режим (`sw1>`)
+    User,
+    /// Привилегированный режим (`sw1#`, `<HUAWEI>`, операционный режим Junos)
+    Privileged,
+    /// Режим конфигурации (`sw1(config)#`, `[HUAWEI]`, `[edit]` в Junos)
+    Config,
+    /// Вложенный режим конфигурации, заданный командой входа (`interface Gi0/1`).
+    /// Пустая команда - вложенный режим, вход в который драйвер не видел
+    SubConfig(String),
+}
+
+impl CliMode {
+    fn level(&self) -> usize {
+        match self {
+            CliMode::User => 0,
+            CliMode::Privileged => 1,
+            CliMode::Config => 2,
+            CliMode::SubConfig(_) => 3,
+        }
+    }
+
+    /// Соседний режим уровнем выше
+    pub fn parent(&self) -> Option<CliMode> {
+        match self {
+            CliMode::User => None,
+            CliMode::Privileged => Some(CliMode::User),
+            CliMode::Config => Some(CliMode::Privileged),
+            CliMode::SubConfig(_) => Some(CliMode::Config),
+        }
+    }
+
+    /// Лежит ли `other` на пути вниз от этого режима (или совпадает с ним)
+    fn contains(&self, other: &CliMode) -> bool {
+        self == other || (self.level() < other.level() && !matches!(self, CliMode::SubConfig(_)))
+    }
+}
+
+impl fmt::Display for CliMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliMode::User => write!(f, "user"),
+            CliMode::Privileged => write!(f, "privileged"),
+            CliMode::Config => write!(f, "config"),
+            CliMode::SubConfig(command) => write!(f, "sub_config '{}'", command),
+        }
+    }
+}
+
+/// Сколько раз подряд выходить из вложенных режимов конфигурации (`router ospf` → `area`)
+pub(crate) const MAX_SUB_CONFIG_DEPTH: usize = 8;
+
+/// Режимы, через которые нужно пройти из `from` в `to` (без `from`):
+/// сначала вверх до общего режима, затем вниз
+pub fn mode_path(from: &CliMode, to: &CliMode) -> Vec<CliMode> {
+    let mut path = Vec::new();
+    let mut current = from.clone();
+    while !current.contains(to) {
+        current = match current.parent() {
+            Some(parent) => parent,
+            None => break,
+        };
+        path.push(current.clone());
+    }
+
+    let mut down = Vec::new();
+    let mut mode = to.clone();
+    while mode != current {
+        down.push(mode.clone());
+        mode = match mode.parent() {
+            Some(parent) => parent,
+            None => break,
+        };
+    }
+    path.extend(down.into_iter().rev());
+    path
+}
+
+/// Ошибка перехода, которого у драйвера нет (например, в `User` на Junos)
+pub(crate) fn no_transition(driver: &str, from: Option<CliMode>, to: &CliMode) -> Error {
+    let from = from.map(|mode| mode.to_string()).unwrap_or_else(|| "?".to_string());
+    Error::Command(format!("{}: нет перехода из режима {} в {}", driver, from, to))
+}
+
+/// Отслеживает вложенный режим конфигурации драйвера: команда входа известна,
+/// пока промпт не сменился
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SubConfigTracker {
+    prompt: String,
+    context: Option<String>,
+}
+
+impl SubConfigTracker {
+    /// Запоминает очередной промпт; смена промпта сбрасывает известную команду входа
+    pub(crate) fn observe(&mut self, prompt: &str, in_sub_config: bool) {
+        if prompt != self.prompt {
+            self.prompt = prompt.to_string();
+            self.context = None;
+        }
+        if !in_sub_config {
+            self.context = None;
+        } else if self.context.is_none() {
+            self.context = Some(String::new());
+        }
+    }
+
+    /// Команда `command` перевела CLI во вложенный режим
+    pub(crate) fn entered(&mut self, command: &str) {
+        if self.context.is_some() {
+            self.context = Some(command.to_string());
+        }
+    }
+
+    /// Режим `SubConfig` с известной командой входа, если CLI во вложенном режиме
+    pub(crate) fn mode(&self) -> Option<CliMode> {
+        self.context.clone().map(CliMode::SubConfig)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sub(command: &str) -> CliMode {
+        CliMode::SubConfig(command.to_string())
+    }
+
+    #[test]
+    fn test_mode_path() {
+        assert_eq!(
+            mode_path(&CliMode::User, &sub("interface Gi0/1")),
+            vec![CliMode::Privileged, CliMode::Config, sub("interface Gi0/1")]
+        );
+        assert_eq!(
+            mode_path(&sub("interface Gi0/1"), &sub("vlan 10")),
+            vec![CliMode::Config, sub("vlan 10")]
+        );
+        assert_eq!(mode_path(&sub(""), &CliMode::Privileged), vec![CliMode::Config, CliMode::Privileged]);
+        assert!(mode_path(&CliMode::Config, &CliMode::Config).is_empty());
+    }
+
+    #[test]
+    fn test_tracker_forgets_context_on_prompt_change() {
+        let mut tracker = SubConfigTracker::default();
+        tracker.observe("sw1(config-if)#", true);
+        tracker.entered("interface Gi0/1");
+        tracker.observe("sw1(config-if)#", true);
+        assert_eq!(tracker.mode(), Some(sub("interface Gi0/1")));
+
+        tracker.observe("sw1(config-vlan)#", true);
+        assert_eq!(tracker.mode(), Some(sub("")));
+        tracker.observe("sw1(config)#", false);
+        assert_eq!(tracker.mode(), None);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::config_engine::types::DeviceConfig;
+use crate::device::commands::DeviceCommands;
+use crate::device::cisco_ios::{self, CiscoIos};
+use crate::device::eltex_esr::{self, EltexEsr};
+use crate::device::eltex_mes::{self, EltexMes};
+use crate::device::generic_cli::{CliProfile, GenericCli};
+use crate::device::huawei_vrp::{self, HuaweiVrp};
+use crate::device::juniper_junos::{self, Junos};
+use crate::device::linux_shell::LinuxShell;
+use crate::device::mikrotik_routeros::{self, RouterOs};
+use crate::device::terminal::OutputNormalizer;
+use crate::error::types::{Error, Result};
+use crate::transport::Transport;
+use std::fmt;
+use std::sync::Arc;
+
+/// Фабрика драйвера: получает подключенный транспорт (с таймаутом устройства)
+/// и конфигурацию устройства
+pub type DriverFactory =
+    dyn Fn(Box<dyn Transport>, &DeviceConfig) -> Result<Box<dyn DeviceCommands>> + Send + Sync;
+
+/// Регулярное выражение промпта драйвера для устройства: с ним `AsyncCliDevice`
+/// работает с тем же устройством без драйвера производителя
+pub type PromptPattern = dyn Fn(&DeviceConfig) -> String + Send + Sync;
+
+#[derive(Clone)]
+struct DriverEntry {
+    vendor: String,
+    model: Option<String>,
+    factory: Arc<DriverFactory>,
+    prompt: Option<Arc<PromptPattern>>,
+}
+
+/// Имя драйвера, описанного только конфигурацией (`extra_params.driver = "generic"`)
+pub const GENERIC_DRIVER: &str = "generic";
+
+/// Реестр драйверов устройств по ключу производитель/модель.
+/// Модель может заканчиваться на `*` (префикс: `MES*`). Точная модель важнее префикса,
+/// префикс важнее драйвера производителя; сравнение без учета регистра. `extra_params.driver` в настройках устройства
+/// задает ключ явно вместо производителя
+#[derive(Clone, Default)]
+pub struct DriverRegistry {
+    drivers: Vec<DriverEntry>,
+}
+
+impl DriverRegistry {
+    /// Пустой реестр
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Реестр со встроенными драйверами
+    pub fn with_builtin_drivers() -> Self {
+        let mut registry = Self::new();
+        registry.register("Eltex", None, |transport, device_config| {
+            let normalizer = OutputNormalizer::from_prompts(&device_config.device_settings.prompts)?;
+            let hostname = eltex_esr::hostname_for(&device_config.device_info);
+            Ok(Box::new(EltexEsr::new(transport, &hostname)?.with_normalizer(normalizer)))
+        });
+        registry.builtin_prompt("Eltex", None, |device_config| {
+            eltex_esr::prompt_pattern(&eltex_esr::hostname_for(&device_config.device_info))
+        });
+        registry.register("Eltex", Some("MES*"), |transport, device_config| {
+            let normalizer = OutputNormalizer::from_prompts(&device_config.device_settings.prompts)?;
+            Ok(Box::new(EltexMes::new(transport)?.with_normalizer(normalizer)))
+        });
+        registry.builtin_prompt("Eltex", Some("MES*"), |_| eltex_mes::PROMPT.to_string());
+        registry.register("Cisco", None, |transport, device_config| {
+            let normalizer = OutputNormalizer::from_prompts(&device_config.device_settings.prompts)?;
+            Ok(Box::new(CiscoIos::new(transport)?.with_normalizer(normalizer)))
+        });
+        registry.builtin_prompt("Cisco", None, |_| cisco_ios::PROMPT.to_string());
+        for vendor in ["Linux", "Ubuntu"] {
+            registry.register(vendor, None, |transport, device_config| {
+                let normalizer = OutputNormalizer::from_prompts(&device_config.device_settings.prompts)?;
+                Ok(Box::new(LinuxShell::new(transport)?.with_normalizer(normalizer)))
+            });
+        }
+        registry.register("Juniper", None, |transport, device_config| {
+            let normalizer = OutputNormalizer::from_prompts(&device_config.device_settings.prompts)?;
+            Ok(Box::new(Junos::new(transport)?.with_normalizer(normalizer)))
+        });
+        registry.builtin_prompt("Juniper", None, |_| juniper_junos::PROMPT.to_string());
+        registry.register("Huawei", None, |transport, device_config| {
+            let normalizer = OutputNormalizer::from_prompts(&device_config.device_settings.prompts)?;
+            Ok(Box::new(HuaweiVrp::new(transport)?.with_normalizer(normalizer)))
+        });
+        registry.builtin_prompt("Huawei", None, |_| huawei_vrp::PROMPT.to_string());
+        registry.register("MikroTik", None, |transport, device_config| {
+            let normalizer = mikrotik_routeros::normalizer(&device_config.device_settings.prompts)?;
+            Ok(Box::new(RouterOs::new(transport)?.with_normalizer(normalizer)))
+        });
+        registry.builtin_prompt("MikroTik", None, |_| mikrotik_routeros::PROMPT.to_string());
+        registry.register(GENERIC_DRIVER, None, |transport, device_config| {
+            let profile = CliProfile::from_settings(&device_config.device_settings)?;
+            Ok(Box::new(GenericCli::new(transport, profile)?))
+        });
+        registry
+    }
+
+    /// Регистрирует драйвер для производителя (`model = None`) или конкретной модели.
+    /// Повторная регистрация того же ключа заменяет драйвер
+    pub fn register<F>(&mut self, vendor: &str, model: Option<&str>, factory: F)
+    where
+        F: Fn(Box<dyn Transport>, &DeviceConfig) -> Result<Box<dyn DeviceCommands>> + Send + Sync + 'static,
+    {
+        self.drivers.retain(|entry| !entry.has_key(vendor, model));
+        self.drivers.push(DriverEntry {
+            vendor: vendor.to_string(),
+            model: model.map(str::to_string),
+            factory: Arc::new(factory),
+            prompt: None,
+        });
+    }
+
+    /// Задает промпт зарегистрированного драйвера (ключ как в `register`).
+    /// Повторная регистрация драйвера сбрасывает промпт
+    pub fn set_prompt_pattern<F>(&mut self, vendor: &str, model: Option<&str>, pattern: F) -> Result<()>
+    where
+        F: Fn(&DeviceConfig) -> String + Send + Sync + 'static,
+    {
+        let entry = self.drivers.iter_mut().find(|entry| entry.has_key(vendor, model)).ok_or_else(|| {
+            Error::ConfigValidation(format!("Драйвер не зарегистрирован: {} {}", vendor, model.unwrap_or("*")))
+        })?;
+        entry.prompt = Some(Arc::new(pattern));
+        Ok(())
+    }
+
+    /// Промпт встроенного драйвера, зарегистрированного строкой выше
+    fn builtin_prompt<F>(&mut self, vendor: &str, model: Option<&str>, pattern: F)
+    where
+        F: Fn(&DeviceConfig) -> String + Send + Sync + 'static,
+    {
+        self.set_prompt_pattern(vendor, model, pattern).expect("встроенный драйвер зарегистрирован");
+    }
+
+    /// Фабрика для производителя и модели, если драйвер зарегистрирован
+    pub fn find(&self, vendor: &str, model: &str) -> Option<Arc<DriverFactory>> {
+        self.find_entry(vendor, model).map(|entry| Arc::clone(&entry.factory))
+    }
+
+    fn find_entry(&self, vendor: &str, model: &str) -> Option<&DriverEntry> {
+        let exact = |entry: &&DriverEntry| entry.has_key(vendor, Some(model));
+        let prefix = |entry: &&DriverEntry| entry.matches_prefix(vendor, model);
+        let any_model = |entry: &&DriverEntry| entry.has_key(vendor, None);
+
+        self.drivers.iter().find(exact)
+            .or_else(|| self.drivers.iter().find(prefix))
+            .or_else(|| self.drivers.iter().find(any_model))
+    }
+
+    /// Драйвер для устройства из конфигурации или ошибка, если его нет
+    fn resolve_entry(&self, device_config: &DeviceConfig) -> Result<&DriverEntry> {
+        let info = &device_config.device_info;
+        let vendor = device_config.device_settings.extra_params.get("driver").unwrap_or(&info.vendor);
+        self.find_entry(vendor, &info.model).ok_or_else(|| Error::ConfigValidation(format!(
+            "Неподдерживаемый производитель: {} (модель {})", vendor, info.model
+        )))
+    }
+
+    /// Фабрика для устройства из конфигурации или ошибка, если драйвера нет
+    pub fn resolve(&self, device_config: &DeviceConfig) -> Result<Arc<DriverFactory>> {
+        self.resolve_entry(device_config).map(|entry| Arc::clone(&entry.factory))
+    }
+
+    /// Промпт драйвера устройства (`set_prompt_pattern`); ошибка, если драйвер его не задал
+    pub fn prompt_pattern(&self, device_config: &DeviceConfig) -> Result<String> {
+        let entry = self.resolve_entry(device_config)?;
+        let prompt = entry.prompt.as_ref().ok_or_else(|| Error::ConfigValidation(format!(
+            "Драйвер {} {} не задает промпт (укажите device_settings.prompts.main)",
+            entry.vendor, entry.model.as_deref().unwrap_or("")
+        )))?;
+        Ok(prompt(device_config))
+    }
+
+    /// Создает драйвер для устройства поверх подключенного транспорта
+    pub fn create(&self, transport: Box<dyn Transport>, device_config: &DeviceConfig) -> Result<Box<dyn DeviceCommands>> {
+        self.resolve(device_config)?(transport, device_config)
+    }
+
+    /// Зарегистрированные ключи (производитель, модель)
+    pub fn keys(&self) -> Vec<(String, Option<String>)> {
+        self.drivers.iter()
+            .map(|entry| (entry.vendor.clone(), entry.model.clone()))
+            .collect()
+    }
+}
+
+impl DriverEntry {
+    /// Совпадение ключа регистрации (модель сравнивается как есть, вместе с `*`)
+    fn has_key(&self, vendor: &str, model: Option<&str>) -> bool {
+        self.vendor.eq_ignore_ascii_case(vendor)
+            && match (&self.model, model) {
+                (Some(own), Some(model)) => own.eq_ignore_ascii_case(model),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+
+    /// Модель устройства начинается с префикса `ПРЕФИКС*` этого драйвера
+    fn matches_prefix(&self, vendor: &str, model: &str) -> bool {
+        let prefix = match self.model.as_deref().and_then(|own| own.strip_suffix('*')) {
+            Some(prefix) => prefix,
+            None => return false,
+        };
+        self.vendor.eq_ignore_ascii_case(vendor)
+            && model.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    }
+}
+
+impl fmt::Debug for DriverRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DriverRegistry").field("drivers", &self.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_engine::{ConfigReader, TomlReader};
+
+    struct Named(&'static str);
+
+    impl DeviceCommands for Named {
+        fn execute_command(&mut self, _command: &str) -> Result<String> {
+            Ok(self.0.to_string())
+        }
+
+        fn disconnect(&mut self) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn device(vendor: &str, model: &str) -> DeviceConfig {
+        let config = format!(r#"
+[devices.d]
+[devices.d.device_info]
+name = "d"
+device_type = "router"
+model = "{}"
+vendor = "{}"
+
+[devices.d.connection]
+transport = "serial"
+host = "/dev/null"
+
+[devices.d.credentials]
+username = "admin"
+password = "admin"
+
+[[devices.d.command_sequence]]
+name = "logout"
+step_type = {{ type = "logout" }}
+on_error = "continue"
+"#, model, vendor);
+        TomlReader::read_from_str(&config).unwrap().devices.remove("d").unwrap()
+    }
+
+    #[test]
+    fn test_model_driver_preferred() {
+        let mut registry = DriverRegistry::new();
+        registry.register("Acme", None, |_, _| Ok(Box::new(Named("vendor"))));
+        registry.register("Acme", Some("X1"), |_, _| Ok(Box::new(Named("model"))));
+        registry.register("Acme", Some("X*"), |_, _| Ok(Box::new(Named("prefix"))));
+
+        let create = |vendor: &str, model: &str| {
+            let transport = Box::new(crate::transport::MockTransport::new());
+            registry.create(transport, &device(vendor, model))
+                .and_then(|mut driver| driver.execute_command(""))
+        };
+
+        assert_eq!(create("acme", "x1").unwrap(), "model");
+        assert_eq!(create("Acme", "X2").unwrap(), "prefix");
+        assert_eq!(create("Acme", "Y1").unwrap(), "vendor");
+        assert!(matches!(create("Other", "X1"), Err(Error::ConfigValidation(_))));
+    }
+
+    #[test]
+    fn test_register_replaces_driver() {
+        let mut registry = DriverRegistry::with_builtin_drivers();
+        let count = registry.keys().len();
+        registry.register("eltex", None, |_, _| Ok(Box::new(Named("custom"))));
+        assert!(registry.keys().contains(&("eltex".to_string(), None)));
+        assert_eq!(registry.keys().len(), count);
+    }
+
+    #[test]
+    fn test_prompt_pattern_per_driver() {
+        let mut registry = DriverRegistry::with_builtin_drivers();
+        assert_eq!(registry.prompt_pattern(&device("Eltex", "ESR-200")).unwrap(), eltex_esr::prompt_pattern("esr-200"));
+        assert_eq!(registry.prompt_pattern(&device("Eltex", "MES2324")).unwrap(), eltex_mes::PROMPT);
+
+        registry.register("Acme", None, |_, _| Ok(Box::new(Named("acme"))));
+        assert!(matches!(registry.prompt_pattern(&device("Acme", "X1")), Err(Error::ConfigValidation(_))));
+        registry.set_prompt_pattern("Acme", None, |config| format!("{}>", config.device_info.model)).unwrap();
+        assert_eq!(registry.prompt_pattern(&device("Acme", "X1")).unwrap(), "X1>");
+        assert!(registry.set_prompt_pattern("Other", None, |_| String::new()).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use crate::error::types::{Error, Result};
+use crate::transport::{ExpectMatch, Regex, Transport};
+#[cfg(feature = "async")]
+use crate::transport::AsyncTransport;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+/// Приглашения постраничного вывода, на которые отвечаем по умолчанию
+const DEFAULT_PAGERS: &[&str] = &[
+    r"(?i)-+ ?more ?-+",
+    r"(?i)<-+ ?more ?-+>",
+    r"(?i)more\? enter",
+    r"(?i)more: <space>",
+    r"(?i)press any key to continue",
+];
+
+/// Ответ на приглашение постраничного вывода - следующая страница
+const DEFAULT_PAGER_ANSWER: &str = " ";
+
+/// Нормализация вывода устройства: ответы на приглашения пейджера при чтении,
+/// затем удаление escape-последовательностей, применение \r и backspace
+/// и удаление эха команды
+#[derive(Debug, Clone)]
+pub struct OutputNormalizer {
+    pagers: Vec<Regex>,
+    pager_answer: String,
+}
+
+impl Default for OutputNormalizer {
+    fn default() -> Self {
+        Self {
+            pagers: DEFAULT_PAGERS.iter()
+                .map(|pattern| Regex::new(pattern).expect("корректный шаблон"))
+                .collect(),
+            pager_answer: DEFAULT_PAGER_ANSWER.to_string(),
+        }
+    }
+}
+
+impl OutputNormalizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Настройки из `DeviceSettings.prompts`: ключи, начинающиеся с "pager", задают
+    /// регулярные выражения приглашений пейджера (заменяют встроенные),
+    /// "pager_answer" - что отправлять в ответ (по умолчанию пробел)
+    pub fn from_prompts(prompts: &HashMap<String, String>) -> Result<Self> {
+        let mut normalizer = Self::default();
+
+        let mut keys: Vec<&String> = prompts.keys()
+            .filter(|key| key.starts_with("pager") && key.as_str() != "pager_answer")
+            .collect();
+        keys.sort();
+        if !keys.is_empty() {
+            normalizer.pagers = keys.into_iter()
+                .map(|key| Regex::new(&prompts[key]).map_err(|e| {
+                    Error::ConfigValidation(format!("prompts.{}: некорректный шаблон: {}", key, e))
+                }))
+                .collect::<Result<_>>()?;
+        }
+        if let Some(answer) = prompts.get("pager_answer") {
+            normalizer.pager_answer = answer.clone();
+        }
+
+        Ok(normalizer)
+    }
+
+    /// Читает вывод до промпта, отвечая на приглашения пейджера.
+    /// Возвращает необработанный текст без промпта и без приглашений пейджера
+    pub fn read_until_prompt<T: Transport + ?Sized>(
+        &self,
+        transport: &mut T,
+        prompt: &Regex,
+        timeout: Duration,
+    ) -> Result<String> {
+        self.read_until_any(transport, std::slice::from_ref(prompt), timeout)
+            .map(|found| found.before)
+    }
+
+    /// Как `read_until_prompt`, но ждет любой из промптов (например, основной или Password:).
+    /// В результате `index` - индекс сработавшего промпта, `before` - весь вывод до него
+    pub fn read_until_any<T: Transport + ?Sized>(
+        &self,
+        transport: &mut T,
+        prompts: &[Regex],
+        timeout: Duration,
+    ) -> Result<ExpectMatch> {
+        let mut patterns = prompts.to_vec();
+        patterns.extend(self.pagers.iter().cloned());
+
+        let deadline = Instant::now() + timeout;
+        let mut output = String::new();
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let found = match transport.expect(&patterns, remaining) {
+                Ok(found) => found,
+                Err(Error::Timeout(rest)) => return Err(Error::Timeout(output + &rest)),
+                Err(e) => return Err(e),
+            };
+
+            output.push_str(&found.before);
+            if found.index < prompts.len() {
+                return Ok(ExpectMatch { before: output, ..found });
+            }
+            transport.send(self.pager_answer.as_bytes())?;
+        }
+    }
+
+    /// Асинхронный вариант `read_until_prompt`
+    #[cfg(feature = "async")]
+    pub async fn read_until_prompt_async<T: AsyncTransport + ?Sized>(
+        &self,
+        transport: &mut T,
+        prompt: &Regex,
+        timeout: Duration,
+    ) -> Result<String> {
+        self.read_until_any_async(transport, std::slice::from_ref(prompt), timeout).await
+            .map(|found| found.before)
+    }
+
+    /// Асинхронный вариант `read_until_any`
+    #[cfg(feature = "async")]
+    pub async fn read_until_any_async<T: AsyncTransport + ?Sized>(
+        &self,
+        transport: &mut T,
+        prompts: &[Regex],
+        timeout: Duration,
+    ) -> Result<ExpectMatch> {
+        let mut patterns = prompts.to_vec();
+        patterns.extend(self.pagers.iter().cloned());
+
+        let deadline = Instant::now() + timeout;
+        let mut output = String::new();
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let found = match transport.expect(&patterns, remaining).await {
+                Ok(found) => found,
+                Err(Error::Timeout(rest)) => return Err(Error::Timeout(output + &rest)),
+                Err(e) => return Err(e),
+            };
+
+            output.push_str(&found.before);
+            if found.index < prompts.len() {
+                return Ok(ExpectMatch { before: output, ..found });
+            }
+            transport.send(self.pager_answer.as_bytes()).await?;
+        }
+    }
+
+    /// Приводит вывод команды к чистому тексту
+    pub fn clean(&self, raw: &str, command: &str) -> String {
+        let text = render(&strip_ansi(raw));
+        let text = remove_echo(&text, command);
+        text.trim_matches('\n').to_string()
+    }
+}
+
+/// Удаляет escape-последовательности ANSI (цвета, перемещение курсора, OSC)
+pub fn strip_ansi(text: &str) -> String {
+    static ESCAPES: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(concat!(
+            r"\x1b\[[0-?]*[ -/]*[@-~]",      // CSI: цвета, курсор, очистка
+            r"|\x1b\][^\x07\x1b]*(\x07|\x1b\\)", // OSC: заголовок окна
+            r"|\x1b[@-Z\\-_]",                // двухсимвольные последовательности
+        )).expect("корректный шаблон")
+    });
+    ESCAPES.replace_all(text, "").to_string()
+}
+
+/// Применяет \r и backspace так, как их отобразил бы терминал,
+/// и убирает пробелы в конце строк
+pub fn render(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let mut cells: Vec<char> = Vec::with_capacity(line.len());
+            let mut column: usize = 0;
+            for ch in line.chars() {
+                match ch {
+                    '\r' => column = 0,
+                    '\x08' => column = column.saturating_sub(1),
+                    '\x07' | '\0' => {}
+                    _ => {
+                        if column < cells.len() {
+                            cells[column] = ch;
+                        } else {
+                            cells.push(ch);
+                        }
+                        column += 1;
+                    }
+                }
+            }
+            cells.into_iter().collect::<String>().trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Удаляет эхо команды: первую непустую строку, если она заканчивается командой
+pub fn remove_echo(text: &str, command: &str) -> String {
+    let command = command.trim();
+    if command.is_empty() {
+        return text.to_string();
+    }
+
+    let mut lines = text.lines().skip_while(|line| line.trim().is_empty()).peekable();
+    if lines.peek().is_some_and(|line| line.trim_end().ends_with(command)) {
+        lines.next();
+    }
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[test]
+    fn test_clean_output() {
+        let raw = "show verz\x08sion\r\n\x1b[1;32mSW version:\x1b[0m 1.4.0\r\n\x1b]0;esr\x07Uptime   \r\n";
+        let clean = OutputNormalizer::new().clean(raw, "show version");
+        assert_eq!(clean, "SW version: 1.4.0\nUptime");
+    }
+
+    #[test]
+    fn test_render_overwrites() {
+        assert_eq!(render("--More--\r        \rline 3"), "line 3");
+        assert_eq!(render("abc\rx"), "xbc");
+        assert_eq!(render("abc\x08 \x08"), "ab");
+    }
+
+    #[test]
+    fn test_pager_answered() {
+        let mut mock = MockTransport::new()
+            .respond("line 1\r\nline 2\r\n --More-- ")
+            .expect_bytes(" ")
+            .respond("\r          \rline 3\r\nesr-200# ");
+        let handle = mock.handle();
+        mock.connect().unwrap();
+
+        let normalizer = OutputNormalizer::new();
+        let prompt = Regex::new(r"esr-200#\s*").unwrap();
+        let raw = normalizer.read_until_prompt(&mut mock, &prompt, Duration::from_secs(1)).unwrap();
+
+        assert_eq!(normalizer.clean(&raw, ""), "line 1\nline 2\nline 3");
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_pagers_from_prompts() {
+        let mut prompts = HashMap::new();
+        prompts.insert("main".to_string(), "esr-200#".to_string());
+        prompts.insert("pager".to_string(), "<MORE>".to_string());
+        prompts.insert("pager_answer".to_string(), "\n".to_string());
+
+        let normalizer = OutputNormalizer::from_prompts(&prompts).unwrap();
+        assert_eq!(normalizer.pagers.len(), 1);
+        assert_eq!(normalizer.pager_answer, "\n");
+
+        prompts.insert("pager".to_string(), "(".to_string());
+        assert!(OutputNormalizer::from_prompts(&prompts).is_err());
+    }
+}
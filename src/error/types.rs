@@ -6,15 +6,27 @@ pub enum Error {
     #[error("Ошибка соединения: {0}")]
     Connection(String),
 
+    /// Устройство отклонило учетные данные или требует сменить пароль
+    #[error("Ошибка аутентификации: {0}")]
+    Authentication(String),
+
     #[error("Ошибка ввода/вывода: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("Ошибка Serial порта: {0}")]
     Serial(#[from] serialport::Error),
 
+    #[error("Ошибка SSH: {0}")]
+    Ssh(#[from] ssh2::Error),
+
+    /// Таймаут с данными, полученными до его истечения
     #[error("Таймаут операции")]
-    Timeout,
+    Timeout(String),
     
+    /// Устройство сообщило об ошибке в выводе команды
+    #[error("Ошибка выполнения команды: {0}")]
+    Command(String),
+
     #[error("Устройство не подключено")]
     NotConnected,
 
@@ -30,6 +42,13 @@ pub enum Error {
     #[error("Ошибка Toml: {0}")]
     Toml(#[from] toml::de::Error),
 
+    /// Вывод команды не разобран парсером
+    #[error("Ошибка разбора вывода: {0}")]
+    Parse(String),
+
+    #[error("Ошибка JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
 
     #[error("Неподерживаемый Формат конфигурации: {0}")]
     UnsupportedFormat(String),
@@ -2,12 +2,14 @@ pub mod error;
 pub mod transport;
 pub mod device;
 pub mod config_engine;
+pub mod parser;
 pub mod cli;
 
 pub use error::types::{Error, Result};
-pub use transport::{SerialTransport, Transport};
+pub use transport::{MockTransport, SerialTransport, SshTransport, TcpTransport, TelnetTransport, Transport};
 pub use config_engine::{
     ConfigFile, DeviceConfig, load_config, create_example_config, TomlReader,
     ConfigExecutor, ExecutionResult, create_executor
 };
+pub use parser::{OutputParser, ParserRegistry};
 pub use cli::{Cli, Commands, execute_command};
\ No newline at end of file
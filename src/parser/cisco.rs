@@ -0,0 +1,162 @@
+use crate::error::types::Result;
+use crate::parser::not_found;
+use crate::transport::Regex;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Сведения из `show version` Cisco IOS / IOS XE
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VersionInfo {
+    pub hostname: Option<String>,
+    pub version: String,
+    pub image: Option<String>,
+    pub uptime: Option<String>,
+    /// Модель из строки процессора: `WS-C2960X-48TS-L`
+    pub model: Option<String>,
+    /// `Processor board ID`
+    pub serial_number: Option<String>,
+    pub config_register: Option<String>,
+}
+
+/// Интерфейс из `show interfaces` Cisco IOS
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceRecord {
+    pub name: String,
+    /// Состояние интерфейса: `up`, `down`, `administratively down`
+    pub status: String,
+    /// Состояние протокола канального уровня
+    pub protocol: String,
+    pub hardware: Option<String>,
+    pub mac_address: Option<String>,
+    pub description: Option<String>,
+    /// Адрес с длиной префикса: `10.0.0.1/24`
+    pub ip_address: Option<String>,
+    pub mtu: Option<u32>,
+    /// Полоса в Кбит/с
+    pub bandwidth: Option<u64>,
+    pub input_errors: Option<u64>,
+    pub output_errors: Option<u64>,
+}
+
+/// Первая группа первого совпадения шаблона в тексте
+fn capture(pattern: &str, text: &str) -> Option<String> {
+    Regex::new(pattern).expect("корректный шаблон")
+        .captures(text)
+        .map(|caps| caps[1].trim().to_string())
+}
+
+/// Число из первой группы шаблона
+fn number<N: FromStr>(pattern: &str, text: &str) -> Option<N> {
+    capture(pattern, text).and_then(|value| value.parse().ok())
+}
+
+/// Разбирает `show version`; без строки версии IOS вывод считается не разобранным
+pub fn parse_show_version(output: &str) -> Result<VersionInfo> {
+    let version = capture(r"(?m)^Cisco IOS.*?Version ([^,\s]+)", output)
+        .ok_or_else(|| not_found("cisco_show_version", "версия IOS"))?;
+    let uptime = Regex::new(r"(?m)^(\S+) uptime is (.+)$").expect("корректный шаблон").captures(output);
+
+    Ok(VersionInfo {
+        hostname: uptime.as_ref().map(|caps| caps[1].to_string()),
+        version,
+        image: capture(r#"System image file is "([^"]+)""#, output),
+        uptime: uptime.as_ref().map(|caps| caps[2].trim().to_string()),
+        model: capture(r"(?mi)^cisco (\S+) .*processor", output),
+        serial_number: capture(r"Processor board ID (\S+)", output),
+        config_register: capture(r"Configuration register is (\S+)", output),
+    })
+}
+
+/// Разбирает `show interfaces`: блок интерфейса начинается строкой без отступа
+/// `GigabitEthernet0/1 is up, line protocol is up`
+pub fn parse_show_interfaces(output: &str) -> Result<Vec<InterfaceRecord>> {
+    let header = Regex::new(r"^(\S+) is (.+?), line protocol is (\S+)").expect("корректный шаблон");
+
+    let mut interfaces: Vec<(InterfaceRecord, String)> = Vec::new();
+    for line in output.lines() {
+        if let Some(caps) = header.captures(line) {
+            let record = InterfaceRecord {
+                name: caps[1].to_string(),
+                status: caps[2].to_string(),
+                protocol: caps[3].to_string(),
+                ..Default::default()
+            };
+            interfaces.push((record, String::new()));
+        } else if let Some((_, body)) = interfaces.last_mut() {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    if interfaces.is_empty() {
+        return Err(not_found("cisco_show_interfaces", "интерфейсы"));
+    }
+
+    Ok(interfaces.into_iter()
+        .map(|(record, body)| InterfaceRecord {
+            hardware: capture(r"Hardware is ([^,\n]+)", &body),
+            mac_address: capture(r"Hardware is [^\n]*?, address is ([0-9a-fA-F.:]+)", &body),
+            description: capture(r"(?m)^\s*Description: (.*)$", &body),
+            ip_address: capture(r"Internet address is (\S+)", &body),
+            mtu: number(r"MTU (\d+) bytes", &body),
+            bandwidth: number(r"BW (\d+) Kbit", &body),
+            input_errors: number(r"(\d+) input errors", &body),
+            output_errors: number(r"(\d+) output errors", &body),
+            ..record
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_show_version() {
+        let output = "Cisco IOS Software, C2960X Software (C2960X-UNIVERSALK9-M), Version 15.2(7)E3, RELEASE SOFTWARE (fc3)\n\
+                      Technical Support: http://www.cisco.com/techsupport\n\
+                      \n\
+                      sw-access1 uptime is 12 weeks, 3 days, 4 hours, 5 minutes\n\
+                      System image file is \"flash:c2960x-universalk9-mz.152-7.E3.bin\"\n\
+                      cisco WS-C2960X-48TS-L (APM86XXX) processor (revision B0) with 524288K bytes of memory.\n\
+                      Processor board ID FOC1234X0AB\n\
+                      Configuration register is 0xF";
+        let info = parse_show_version(output).unwrap();
+
+        assert_eq!(info.version, "15.2(7)E3");
+        assert_eq!(info.hostname.as_deref(), Some("sw-access1"));
+        assert_eq!(info.uptime.as_deref(), Some("12 weeks, 3 days, 4 hours, 5 minutes"));
+        assert_eq!(info.image.as_deref(), Some("flash:c2960x-universalk9-mz.152-7.E3.bin"));
+        assert_eq!(info.model.as_deref(), Some("WS-C2960X-48TS-L"));
+        assert_eq!(info.serial_number.as_deref(), Some("FOC1234X0AB"));
+        assert_eq!(info.config_register.as_deref(), Some("0xF"));
+
+        assert!(parse_show_version("% Invalid input detected").is_err());
+    }
+
+    #[test]
+    fn test_parse_show_interfaces() {
+        let output = "GigabitEthernet0/1 is up, line protocol is up (connected)\n  \
+                      Hardware is Gigabit Ethernet, address is 0c11.2233.4401 (bia 0c11.2233.4401)\n  \
+                      Description: uplink to core\n  \
+                      Internet address is 10.0.0.2/30\n  \
+                      MTU 1500 bytes, BW 1000000 Kbit/sec, DLY 10 usec,\n     \
+                      0 input errors, 0 CRC, 0 frame, 0 overrun, 0 ignored\n     \
+                      3 output errors, 0 collisions, 1 interface resets\n\
+                      GigabitEthernet0/2 is administratively down, line protocol is down (disabled)\n  \
+                      Hardware is Gigabit Ethernet, address is 0c11.2233.4402 (bia 0c11.2233.4402)\n  \
+                      MTU 1500 bytes, BW 10000 Kbit/sec, DLY 1000 usec,";
+        let interfaces = parse_show_interfaces(output).unwrap();
+
+        assert_eq!(interfaces.len(), 2);
+        assert_eq!(interfaces[0].name, "GigabitEthernet0/1");
+        assert_eq!(interfaces[0].protocol, "up");
+        assert_eq!(interfaces[0].description.as_deref(), Some("uplink to core"));
+        assert_eq!(interfaces[0].ip_address.as_deref(), Some("10.0.0.2/30"));
+        assert_eq!(interfaces[0].mac_address.as_deref(), Some("0c11.2233.4401"));
+        assert_eq!(interfaces[0].bandwidth, Some(1_000_000));
+        assert_eq!(interfaces[0].output_errors, Some(3));
+        assert_eq!(interfaces[1].status, "administratively down");
+        assert_eq!(interfaces[1].description, None);
+        assert_eq!(interfaces[1].mtu, Some(1500));
+    }
+}
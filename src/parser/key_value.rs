@@ -0,0 +1,117 @@
+use crate::error::types::Result;
+use crate::parser::{field_name, not_found};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Строки `Подпись: значение` → поля по именам подписей (`field_name`).
+/// Значение берется после первого двоеточия вне скобок, строки без подписи пропускаются
+pub fn parse_key_value(output: &str) -> Result<BTreeMap<String, String>> {
+    let fields: BTreeMap<String, String> = output.lines()
+        .filter_map(split_label)
+        .map(|(label, value)| (field_name(label), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect();
+
+    if fields.is_empty() {
+        return Err(not_found("key_value", "строки 'подпись: значение'"));
+    }
+    Ok(fields)
+}
+
+/// `System Up Time (days,hour:min:sec): 00,00:05:25` → подпись и значение
+fn split_label(line: &str) -> Option<(&str, &str)> {
+    let mut depth = 0i32;
+    let colon = line.char_indices().find(|&(_, c)| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        c == ':' && depth <= 0
+    })?;
+    Some((&line[..colon.0], &line[colon.0 + 1..]))
+}
+
+/// Сведения о системе из `show system` (Eltex ESR и MES)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SystemInfo {
+    /// `System type` (ESR): `Eltex Service Router ESR-200`
+    pub system_type: Option<String>,
+    /// `System Description` (MES)
+    pub description: Option<String>,
+    pub name: Option<String>,
+    pub software_version: Option<String>,
+    pub hardware_version: Option<String>,
+    pub serial_number: Option<String>,
+    pub mac_address: Option<String>,
+    /// Время работы в записи устройства (`1 days, 2 hours...` или `00,00:05:25`)
+    pub uptime: Option<String>,
+    /// Остальные непустые поля вывода
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub other: BTreeMap<String, String>,
+}
+
+/// Разбирает `show system`; подписи `System ...` и без префикса равнозначны
+pub fn parse_show_system(output: &str) -> Result<SystemInfo> {
+    let mut info = SystemInfo::default();
+    for (name, value) in parse_key_value(output)? {
+        if value.is_empty() {
+            continue;
+        }
+        let field = match name.strip_prefix("system_").unwrap_or(&name) {
+            "type" => &mut info.system_type,
+            "description" => &mut info.description,
+            "name" => &mut info.name,
+            "software_version" | "firmware_version" => &mut info.software_version,
+            "hardware_version" => &mut info.hardware_version,
+            "serial_number" => &mut info.serial_number,
+            "mac_address" => &mut info.mac_address,
+            "uptime" | "up_time" => &mut info.uptime,
+            _ => {
+                info.other.insert(name, value);
+                continue;
+            }
+        };
+        *field = Some(value);
+    }
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_show_system_esr() {
+        let output = "System type:           Eltex Service Router ESR-200\n\
+                      System name:           esr-200\n\
+                      Software version:      1.11.0 (date 25/12/2020 time 11:22:12)\n\
+                      Hardware version:      1v3\n\
+                      System uptime:         1 days, 2 hours, 3 minutes and 4 seconds\n\
+                      System MAC address:    A8:F9:4B:AA:00:40\n\
+                      System serial number:  NP00000000\n\
+                      Temperature:           41C";
+        let info = parse_show_system(output).unwrap();
+
+        assert_eq!(info.system_type.as_deref(), Some("Eltex Service Router ESR-200"));
+        assert_eq!(info.name.as_deref(), Some("esr-200"));
+        assert_eq!(info.software_version.as_deref(), Some("1.11.0 (date 25/12/2020 time 11:22:12)"));
+        assert_eq!(info.mac_address.as_deref(), Some("A8:F9:4B:AA:00:40"));
+        assert_eq!(info.serial_number.as_deref(), Some("NP00000000"));
+        assert_eq!(info.other["temperature"], "41C");
+    }
+
+    #[test]
+    fn test_parse_show_system_mes() {
+        let output = "System Description:                       MES2324B 28-port 1G/10G Managed Aggregation Switch\n\
+                      System Up Time (days,hour:min:sec):       00,00:05:25\n\
+                      System Contact:\n\
+                      System Name:                              mes2324";
+        let info = parse_show_system(output).unwrap();
+
+        assert_eq!(info.uptime.as_deref(), Some("00,00:05:25"));
+        assert_eq!(info.name.as_deref(), Some("mes2324"));
+        assert!(info.other.is_empty());
+        assert!(parse_show_system("% Unrecognized command").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+pub mod cisco;
+pub mod key_value;
+pub mod registry;
+pub mod table;
+pub mod textfsm;
+
+use crate::error::types::{Error, Result};
+use serde::Serialize;
+
+pub use cisco::{parse_show_interfaces, parse_show_version, InterfaceRecord, VersionInfo};
+pub use key_value::{parse_key_value, parse_show_system, SystemInfo};
+pub use registry::ParserRegistry;
+pub use table::{parse_table, TableRow};
+pub use textfsm::{FieldValue, TextFsm, TextFsmRecord};
+
+/// Разбор вывода команды в структурированные данные.
+/// Результат - JSON, чтобы хранить записи любых парсеров рядом с сырым выводом
+pub trait OutputParser: Send + Sync {
+    fn parse(&self, output: &str) -> Result<serde_json::Value>;
+}
+
+/// Функция разбора в типизированную запись (`parse_show_version` и т.п.) - тоже парсер
+impl<F, R> OutputParser for F
+where
+    F: Fn(&str) -> Result<R> + Send + Sync,
+    R: Serialize,
+{
+    fn parse(&self, output: &str) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self(output)?)?)
+    }
+}
+
+/// Имя поля из подписи устройства: `System Up Time (days,hour:min:sec)` → `system_up_time`
+pub(crate) fn field_name(label: &str) -> String {
+    let mut name = String::new();
+    let mut depth = 0;
+    for c in label.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() => name.extend(c.to_lowercase()),
+            _ if !name.is_empty() && !name.ends_with('_') => name.push('_'),
+            _ => {}
+        }
+    }
+    name.trim_end_matches('_').to_string()
+}
+
+/// Ошибка разбора: в выводе нет ожидаемых данных
+pub(crate) fn not_found(parser: &str, what: &str) -> Error {
+    Error::Parse(format!("{}: {} не найдено в выводе", parser, what))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("System Up Time (days,hour:min:sec)"), "system_up_time");
+        assert_eq!(field_name("  System MAC address"), "system_mac_address");
+        assert_eq!(field_name("Port-Channel"), "port_channel");
+    }
+}
@@ -0,0 +1,114 @@
+use crate::error::types::{Error, Result};
+use crate::parser::{cisco, key_value, table, OutputParser, TextFsm};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Реестр парсеров вывода по имени; шаг команды выбирает парсер полем `parser`
+#[derive(Clone, Default)]
+pub struct ParserRegistry {
+    parsers: BTreeMap<String, Arc<dyn OutputParser>>,
+}
+
+impl ParserRegistry {
+    /// Пустой реестр
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Реестр со встроенными парсерами
+    pub fn with_builtin_parsers() -> Self {
+        let mut registry = Self::new();
+        registry.register("key_value", key_value::parse_key_value);
+        registry.register("table", table::parse_table);
+        registry.register("show_system", key_value::parse_show_system);
+        registry.register("cisco_show_version", cisco::parse_show_version);
+        registry.register("cisco_show_interfaces", cisco::parse_show_interfaces);
+        registry
+    }
+
+    /// Регистрирует парсер; повторная регистрация имени заменяет парсер
+    pub fn register<P: OutputParser + 'static>(&mut self, name: &str, parser: P) {
+        self.parsers.insert(name.to_string(), Arc::new(parser));
+    }
+
+    /// Регистрирует шаблоны TextFSM (`*.textfsm`) из каталога под именами файлов без
+    /// расширения: `cisco_ios_show_version.textfsm` → `cisco_ios_show_version`.
+    /// Возвращает количество шаблонов
+    pub fn load_templates<P: AsRef<Path>>(&mut self, dir: P) -> Result<usize> {
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<_>>()?;
+        paths.retain(|path| path.extension().is_some_and(|extension| extension == "textfsm"));
+        paths.sort();
+
+        for path in &paths {
+            let name = path.file_stem().and_then(|stem| stem.to_str()).ok_or_else(|| Error::ConfigValidation(
+                format!("Некорректное имя шаблона: {}", path.display())
+            ))?;
+            self.register(name, TextFsm::from_file(path)?);
+        }
+        Ok(paths.len())
+    }
+
+    /// Парсер по имени, если он зарегистрирован
+    pub fn get(&self, name: &str) -> Option<Arc<dyn OutputParser>> {
+        self.parsers.get(name).cloned()
+    }
+
+    /// Разбирает вывод парсером `name`
+    pub fn parse(&self, name: &str, output: &str) -> Result<serde_json::Value> {
+        let parser = self.get(name).ok_or_else(|| Error::ConfigValidation(format!(
+            "Неизвестный парсер: {} (доступны: {})", name, self.names().join(", ")
+        )))?;
+        parser.parse(output)
+    }
+
+    /// Зарегистрированные имена
+    pub fn names(&self) -> Vec<String> {
+        self.parsers.keys().cloned().collect()
+    }
+}
+
+impl fmt::Debug for ParserRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParserRegistry").field("parsers", &self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_and_custom_parsers() {
+        let mut registry = ParserRegistry::with_builtin_parsers();
+        assert_eq!(registry.names().len(), 5);
+
+        let value = registry.parse("show_system", "System name: esr-200").unwrap();
+        assert_eq!(value["name"], "esr-200");
+        assert!(matches!(registry.parse("show_system", "% Unrecognized command"), Err(Error::Parse(_))));
+        assert!(matches!(registry.parse("missing", ""), Err(Error::ConfigValidation(_))));
+
+        registry.register("lines", |output: &str| -> Result<Vec<String>> {
+            Ok(output.lines().map(str::to_string).collect())
+        });
+        assert_eq!(registry.parse("lines", "a\nb").unwrap(), serde_json::json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_load_templates() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("linux_uname.textfsm"),
+            "Value KERNEL (\\S+)\nValue RELEASE (\\S+)\n\nStart\n  ^${KERNEL} ${RELEASE} -> Record\n",
+        ).unwrap();
+        std::fs::write(dir.path().join("README.md"), "не шаблон").unwrap();
+
+        let mut registry = ParserRegistry::new();
+        assert_eq!(registry.load_templates(dir.path()).unwrap(), 1);
+        let value = registry.parse("linux_uname", "Linux 6.1.0").unwrap();
+        assert_eq!(value, serde_json::json!([{ "KERNEL": "Linux", "RELEASE": "6.1.0" }]));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::error::types::Result;
+use crate::parser::{field_name, not_found};
+use std::collections::BTreeMap;
+
+/// Строка таблицы: имя колонки (`field_name` заголовка) → значение
+pub type TableRow = BTreeMap<String, String>;
+
+/// Строка-разделитель под заголовком: `------- ------ ----` или `====`
+fn is_separator(line: &str) -> bool {
+    line.contains(['-', '='])
+        && line.chars().all(|c| matches!(c, '-' | '=' | '+' | ' ' | '\t'))
+}
+
+/// Начала колонок: каждое слово заголовка или серия `-` разделителя - колонка
+fn column_starts(line: &[char]) -> Vec<usize> {
+    (0..line.len())
+        .filter(|&i| line[i] != ' ' && (i == 0 || line[i - 1] == ' '))
+        .collect()
+}
+
+/// Текст колонки `index` в строке
+fn cell(line: &[char], columns: &[usize], index: usize) -> String {
+    let start = columns[index].min(line.len());
+    let end = columns.get(index + 1).copied().unwrap_or(line.len()).min(line.len());
+    line[start..end].iter().collect::<String>().trim().to_string()
+}
+
+/// Разбирает таблицу с заголовком по позициям колонок. Колонки задает строка-разделитель
+/// под заголовком, если она есть, иначе - слова заголовка
+pub fn parse_table(output: &str) -> Result<Vec<TableRow>> {
+    let lines: Vec<Vec<char>> = output.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.replace('\t', " ").trim_end().chars().collect())
+        .collect();
+    let text = |line: &[char]| line.iter().collect::<String>();
+
+    let header_index = match lines.iter().position(|line| is_separator(&text(line))) {
+        Some(0) | None => 0,
+        Some(separator) => separator - 1,
+    };
+    let header = lines.get(header_index).ok_or_else(|| not_found("table", "заголовок таблицы"))?;
+    let columns = match lines.get(header_index + 1) {
+        Some(separator) if is_separator(&text(separator)) => column_starts(separator),
+        _ => column_starts(header),
+    };
+
+    let names: Vec<String> = (0..columns.len())
+        .map(|index| match field_name(&cell(header, &columns, index)) {
+            name if name.is_empty() => format!("column_{}", index + 1),
+            name => name,
+        })
+        .collect();
+    let rows: Vec<TableRow> = lines[header_index + 1..].iter()
+        .filter(|line| !is_separator(&text(line)))
+        .map(|line| names.iter().enumerate()
+            .map(|(index, name)| (name.clone(), cell(line, &columns, index)))
+            .collect())
+        .collect();
+
+    if rows.is_empty() {
+        return Err(not_found("table", "строки таблицы"));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_table_with_separator() {
+        let output = "Interface   Admin State  Link State  Description\n\
+                      ----------  -----------  ----------  ------------------\n\
+                      gi1/0/1     Up           Up          uplink to core\n\
+                      gi1/0/2     Down         Down\n";
+        let rows = parse_table(output).unwrap();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["admin_state"], "Up");
+        assert_eq!(rows[0]["description"], "uplink to core");
+        assert_eq!(rows[1]["interface"], "gi1/0/2");
+        assert_eq!(rows[1]["description"], "");
+    }
+
+    #[test]
+    fn test_parse_table_by_header_words() {
+        let output = "Interface              IP-Address      OK? Method Status                Protocol\n\
+                      GigabitEthernet0/0     10.0.0.1        YES NVRAM  up                    up\n\
+                      GigabitEthernet0/1     unassigned      YES unset  administratively down down";
+        let rows = parse_table(output).unwrap();
+
+        assert_eq!(rows[0]["ip_address"], "10.0.0.1");
+        assert_eq!(rows[1]["status"], "administratively down");
+        assert_eq!(rows[1]["protocol"], "down");
+        assert!(parse_table("Interface  Status").is_err());
+    }
+}
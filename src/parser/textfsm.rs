@@ -0,0 +1,528 @@
+use crate::error::types::{Error, Result};
+use crate::parser::OutputParser;
+use crate::transport::Regex;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Состояния, которые не описываются в шаблоне: `End` завершает разбор,
+/// `EOF` - тоже, а описанное в шаблоне `EOF` отключает неявную запись в конце текста
+const END_STATE: &str = "End";
+const EOF_STATE: &str = "EOF";
+
+/// Значение поля записи: строка или список для `Value List`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Text(String),
+    List(Vec<String>),
+}
+
+impl FieldValue {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            FieldValue::Text(text) => text.is_empty(),
+            FieldValue::List(items) => items.is_empty(),
+        }
+    }
+}
+
+/// Запись разбора: имя `Value` → значение
+pub type TextFsmRecord = BTreeMap<String, FieldValue>;
+
+/// `Value [Filldown,Key,Required,List,Fillup] Имя (регулярное выражение)`
+#[derive(Debug, Clone)]
+struct ValueDef {
+    name: String,
+    /// Выражение значения с именованной группой: `(?P<Имя>...)`
+    pattern: String,
+    filldown: bool,
+    required: bool,
+    list: bool,
+    fillup: bool,
+}
+
+impl ValueDef {
+    fn empty(&self) -> FieldValue {
+        if self.list {
+            FieldValue::List(Vec::new())
+        } else {
+            FieldValue::Text(String::new())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineAction {
+    Next,
+    Continue,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecordAction {
+    NoRecord,
+    Record,
+    Clear,
+    Clearall,
+}
+
+/// `^регулярное выражение -> Действие.Запись НовоеСостояние`
+#[derive(Debug, Clone)]
+struct Rule {
+    regex: Regex,
+    line_action: LineAction,
+    record_action: RecordAction,
+    /// Новое состояние или сообщение `Error "..."`
+    target: Option<String>,
+}
+
+/// Шаблон TextFSM (формат Google TextFSM и ntc-templates): определения `Value`,
+/// состояния начиная со `Start` и правила с действиями `Next`/`Continue`/`Error`
+/// и `Record`/`NoRecord`/`Clear`/`Clearall`. Регулярные выражения - синтаксис crate `regex`
+/// (без lookaround и обратных ссылок)
+#[derive(Debug, Clone)]
+pub struct TextFsm {
+    values: Vec<ValueDef>,
+    states: HashMap<String, Vec<Rule>>,
+}
+
+/// Ошибка в шаблоне с номером строки
+fn template_error(line: usize, message: impl std::fmt::Display) -> Error {
+    Error::ConfigValidation(format!("шаблон TextFSM, строка {}: {}", line, message))
+}
+
+impl TextFsm {
+    /// Разбирает текст шаблона
+    pub fn new(template: &str) -> Result<Self> {
+        let mut lines = template.lines().enumerate().map(|(index, line)| (index + 1, line));
+
+        let mut values: Vec<ValueDef> = Vec::new();
+        for (number, line) in lines.by_ref() {
+            if line.trim().is_empty() {
+                break;
+            }
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+            let value = parse_value(number, line)?;
+            if values.iter().any(|other| other.name == value.name) {
+                return Err(template_error(number, format!("повторное определение Value {}", value.name)));
+            }
+            values.push(value);
+        }
+
+        let patterns: HashMap<&str, &str> = values.iter()
+            .map(|value| (value.name.as_str(), value.pattern.as_str()))
+            .collect();
+        let mut states: HashMap<String, Vec<Rule>> = HashMap::new();
+        let mut current: Option<String> = None;
+        let mut targets = Vec::new();
+        for (number, line) in lines {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                current = None;
+            } else if trimmed.starts_with('#') {
+                continue;
+            } else if !line.starts_with([' ', '\t']) {
+                if !trimmed.chars().all(|c| c.is_alphanumeric() || c == '_') || trimmed == END_STATE {
+                    return Err(template_error(number, format!("некорректное имя состояния '{}'", trimmed)));
+                }
+                if states.insert(trimmed.to_string(), Vec::new()).is_some() {
+                    return Err(template_error(number, format!("повторное состояние {}", trimmed)));
+                }
+                current = Some(trimmed.to_string());
+            } else {
+                let state = current.as_ref().ok_or_else(|| template_error(number, "правило вне состояния"))?;
+                let rule = parse_rule(number, trimmed, &patterns)?;
+                if rule.line_action != LineAction::Error {
+                    if let Some(target) = &rule.target {
+                        targets.push((number, target.clone()));
+                    }
+                }
+                states.get_mut(state).expect("состояние добавлено").push(rule);
+            }
+        }
+
+        if !states.contains_key("Start") {
+            return Err(template_error(0, "нет состояния Start"));
+        }
+        if states.get(EOF_STATE).is_some_and(|rules| !rules.is_empty()) {
+            return Err(template_error(0, "состояние EOF не может содержать правил"));
+        }
+        if let Some((number, target)) = targets.iter()
+            .find(|(_, target)| target != END_STATE && target != EOF_STATE && !states.contains_key(target))
+        {
+            return Err(template_error(*number, format!("переход в неизвестное состояние {}", target)));
+        }
+        Ok(Self { values, states })
+    }
+
+    /// Читает шаблон из файла (`.textfsm` из ntc-templates и т.п.)
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let template = std::fs::read_to_string(path)?;
+        Self::new(&template).map_err(|e| match e {
+            Error::ConfigValidation(message) => Error::ConfigValidation(format!("{}: {}", path.display(), message)),
+            e => e,
+        })
+    }
+
+    /// Имена `Value` в порядке определения (колонки таблицы)
+    pub fn header(&self) -> Vec<&str> {
+        self.values.iter().map(|value| value.name.as_str()).collect()
+    }
+
+    /// Разбирает текст в таблицу записей
+    pub fn parse_text(&self, text: &str) -> Result<Vec<TextFsmRecord>> {
+        let mut run = Run {
+            values: &self.values,
+            current: self.values.iter().map(ValueDef::empty).collect(),
+            records: Vec::new(),
+        };
+
+        let mut state = "Start";
+        'lines: for line in text.lines() {
+            for rule in &self.states[state] {
+                let Some(caps) = rule.regex.captures(line) else { continue };
+                for (index, value) in self.values.iter().enumerate() {
+                    if rule.regex.capture_names().flatten().any(|name| name == value.name) {
+                        run.assign(index, caps.name(&value.name).map(|found| found.as_str()));
+                    }
+                }
+
+                if rule.line_action == LineAction::Error {
+                    let message = rule.target.as_deref().unwrap_or("правило Error");
+                    return Err(Error::Parse(format!("TextFSM: {} (строка '{}')", message, line)));
+                }
+                match rule.record_action {
+                    RecordAction::Record => run.record(),
+                    RecordAction::Clear => run.clear(),
+                    RecordAction::Clearall => run.clear_all(),
+                    RecordAction::NoRecord => {}
+                }
+                if rule.line_action == LineAction::Continue {
+                    continue;
+                }
+                if let Some(target) = &rule.target {
+                    state = target;
+                }
+                if state == END_STATE || state == EOF_STATE {
+                    break 'lines;
+                }
+                continue 'lines;
+            }
+        }
+
+        // Неявная запись в конце текста, если шаблон не описал свое EOF
+        if state != END_STATE && !self.states.contains_key(EOF_STATE) {
+            run.record();
+        }
+        Ok(run.records.into_iter()
+            .map(|row| self.values.iter().map(|value| value.name.clone()).zip(row).collect())
+            .collect())
+    }
+}
+
+impl OutputParser for TextFsm {
+    fn parse(&self, output: &str) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(self.parse_text(output)?)?)
+    }
+}
+
+/// Значения текущей записи и готовые записи одного разбора
+struct Run<'a> {
+    values: &'a [ValueDef],
+    current: Vec<FieldValue>,
+    records: Vec<Vec<FieldValue>>,
+}
+
+impl Run<'_> {
+    fn assign(&mut self, index: usize, text: Option<&str>) {
+        let value = &self.values[index];
+        match (&mut self.current[index], text) {
+            (FieldValue::List(items), Some(text)) => items.push(text.to_string()),
+            (FieldValue::List(_), None) => {}
+            (field, text) => *field = FieldValue::Text(text.unwrap_or_default().to_string()),
+        }
+
+        // Fillup: значение заполняет пустые поля предыдущих записей снизу вверх
+        if let (true, Some(text)) = (value.fillup, text) {
+            for record in self.records.iter_mut().rev() {
+                if !record[index].is_empty() {
+                    break;
+                }
+                record[index] = FieldValue::Text(text.to_string());
+            }
+        }
+    }
+
+    /// Сохраняет запись; без обязательного (`Required`) значения запись отбрасывается
+    fn record(&mut self) {
+        if self.values.iter().zip(&self.current).any(|(value, field)| value.required && field.is_empty()) {
+            self.clear();
+            return;
+        }
+        if self.current.iter().all(FieldValue::is_empty) {
+            return;
+        }
+        self.records.push(self.current.clone());
+        self.clear();
+    }
+
+    /// Очищает значения, кроме `Filldown`
+    fn clear(&mut self) {
+        for (value, field) in self.values.iter().zip(self.current.iter_mut()) {
+            if !value.filldown {
+                *field = value.empty();
+            }
+        }
+    }
+
+    fn clear_all(&mut self) {
+        self.current = self.values.iter().map(ValueDef::empty).collect();
+    }
+}
+
+fn parse_value(number: usize, line: &str) -> Result<ValueDef> {
+    let rest = line.strip_prefix("Value ")
+        .ok_or_else(|| template_error(number, "ожидается строка Value или пустая строка перед состояниями"))?
+        .trim_start();
+    let (first, rest) = rest.split_once([' ', '\t'])
+        .ok_or_else(|| template_error(number, "ожидается имя и регулярное выражение"))?;
+    let rest = rest.trim_start();
+    let (options, name, regex) = if rest.starts_with('(') {
+        ("", first, rest)
+    } else {
+        let (name, regex) = rest.split_once([' ', '\t'])
+            .ok_or_else(|| template_error(number, "ожидается регулярное выражение"))?;
+        (first, name, regex.trim_start())
+    };
+
+    let regex = regex.trim_end();
+    if !regex.starts_with('(') || !regex.ends_with(')') {
+        return Err(template_error(number, format!("выражение Value {} должно быть в скобках", name)));
+    }
+    let mut value = ValueDef {
+        name: name.to_string(),
+        pattern: format!("(?P<{}>{}", name, &regex[1..]),
+        filldown: false,
+        required: false,
+        list: false,
+        fillup: false,
+    };
+    for option in options.split(',').filter(|option| !option.is_empty()) {
+        match option {
+            "Filldown" => value.filldown = true,
+            "Required" => value.required = true,
+            "List" => value.list = true,
+            "Fillup" => value.fillup = true,
+            "Key" => {}
+            other => return Err(template_error(number, format!("неизвестная опция Value {}", other))),
+        }
+    }
+    Regex::new(&value.pattern).map_err(|e| template_error(number, e))?;
+    Ok(value)
+}
+
+/// Подставляет `${Имя}`/`$Имя` значений в правило; `$$` - конец строки
+fn expand_rule(number: usize, rule: &str, patterns: &HashMap<&str, &str>) -> Result<String> {
+    let mut expanded = String::new();
+    let mut chars = rule.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+        let name: String = match chars.peek() {
+            Some('$') => {
+                chars.next();
+                expanded.push('$');
+                continue;
+            }
+            Some('{') => {
+                chars.next();
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                name
+            }
+            _ => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_alphanumeric() || **c == '_') {
+                    name.push(c);
+                    chars.next();
+                }
+                name
+            }
+        };
+        if name.is_empty() {
+            // `$` в конце выражения - якорь конца строки
+            expanded.push('$');
+            continue;
+        }
+        let pattern = patterns.get(name.as_str())
+            .ok_or_else(|| template_error(number, format!("неизвестное значение ${{{}}}", name)))?;
+        expanded.push_str(pattern);
+    }
+    Ok(expanded)
+}
+
+fn parse_rule(number: usize, line: &str, patterns: &HashMap<&str, &str>) -> Result<Rule> {
+    if !line.starts_with('^') {
+        return Err(template_error(number, "правило должно начинаться с ^"));
+    }
+    let (regex, action) = match line.rfind(" ->") {
+        Some(at) => (&line[..at], line[at + 3..].trim()),
+        None => (line, ""),
+    };
+
+    let mut rule = Rule {
+        regex: Regex::new(&expand_rule(number, regex, patterns)?).map_err(|e| template_error(number, e))?,
+        line_action: LineAction::Next,
+        record_action: RecordAction::NoRecord,
+        target: None,
+    };
+    let (operation, target) = match action.split_once([' ', '\t']) {
+        Some((operation, target)) => (operation, Some(target.trim())),
+        None => (action, None),
+    };
+
+    let (line_action, record_action) = match operation.split_once('.') {
+        Some((line_action, record_action)) => (Some(line_action), Some(record_action)),
+        None => (Some(operation), None),
+    };
+    let mut target = target.map(str::to_string);
+    match line_action {
+        Some("Next") => {}
+        Some("Continue") => rule.line_action = LineAction::Continue,
+        Some("Error") => rule.line_action = LineAction::Error,
+        Some("") | None => {}
+        Some(other) => match parse_record_action(other) {
+            // `-> Record State`
+            Some(action) if record_action.is_none() => rule.record_action = action,
+            // `-> State`
+            _ if record_action.is_none() && target.is_none() => target = Some(other.to_string()),
+            _ => return Err(template_error(number, format!("неизвестное действие {}", other))),
+        },
+    }
+    if let Some(record_action) = record_action {
+        rule.record_action = parse_record_action(record_action)
+            .ok_or_else(|| template_error(number, format!("неизвестное действие записи {}", record_action)))?;
+    }
+
+    if rule.line_action == LineAction::Continue && target.is_some() {
+        return Err(template_error(number, "Continue не может менять состояние"));
+    }
+    rule.target = target.map(|target| target.trim_matches('"').to_string());
+    if rule.line_action != LineAction::Error {
+        if let Some(target) = &rule.target {
+            if !target.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(template_error(number, format!("некорректное имя состояния '{}'", target)));
+            }
+        }
+    }
+    Ok(rule)
+}
+
+fn parse_record_action(action: &str) -> Option<RecordAction> {
+    match action {
+        "NoRecord" => Some(RecordAction::NoRecord),
+        "Record" => Some(RecordAction::Record),
+        "Clear" => Some(RecordAction::Clear),
+        "Clearall" => Some(RecordAction::Clearall),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Шаблон ntc-templates `cisco_ios_show_ip_interface_brief`
+    const IP_BRIEF: &str = r#"Value INTF (\S+)
+Value IPADDR (\S+)
+Value STATUS (up|down|administratively down)
+Value PROTO (up|down)
+
+Start
+  ^${INTF}\s+${IPADDR}\s+\w+\s+\w+\s+${STATUS}\s+${PROTO} -> Record
+"#;
+
+    fn text(value: &str) -> FieldValue {
+        FieldValue::Text(value.to_string())
+    }
+
+    #[test]
+    fn test_records_from_table() {
+        let template = TextFsm::new(IP_BRIEF).unwrap();
+        let output = "Interface              IP-Address      OK? Method Status                Protocol\n\
+                      GigabitEthernet0/0     10.0.0.1        YES NVRAM  up                    up\n\
+                      GigabitEthernet0/1     unassigned      YES unset  administratively down down";
+        let records = template.parse_text(output).unwrap();
+
+        assert_eq!(template.header(), ["INTF", "IPADDR", "STATUS", "PROTO"]);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["IPADDR"], text("10.0.0.1"));
+        assert_eq!(records[1]["STATUS"], text("administratively down"));
+    }
+
+    #[test]
+    fn test_filldown_list_required_and_states() {
+        let template = TextFsm::new(r#"# VLAN и порты
+Value Filldown SWITCH (\S+)
+Value Required VLAN (\d+)
+Value NAME (\S+)
+Value List PORTS (\S+)
+
+Start
+  ^Switch ${SWITCH}
+  ^VLAN -> Vlans
+
+Vlans
+  ^\d+\s -> Continue.Record
+  ^${VLAN}\s+${NAME}\s+${PORTS}
+  ^${VLAN}\s+${NAME}\s*$$
+  ^\s+${PORTS}
+  ^END -> End
+"#).unwrap();
+        let output = "Switch sw1\nVLAN Name Ports\n----\n1 default Gi0/1\n  Gi0/2\n10 users Gi0/3\n20 empty\nEND\n30 ignored Gi0/9";
+        let records = template.parse_text(output).unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["SWITCH"], text("sw1"));
+        assert_eq!(records[0]["PORTS"], FieldValue::List(vec!["Gi0/1".to_string(), "Gi0/2".to_string()]));
+        assert_eq!(records[1]["VLAN"], text("10"));
+        assert_eq!(records[1]["SWITCH"], text("sw1"));
+    }
+
+    #[test]
+    fn test_fillup_clear_eof_and_error() {
+        let template = TextFsm::new(r#"Value Fillup AREA (\d+)
+Value ROUTER (\S+)
+
+Start
+  ^router ${ROUTER} -> Record
+  ^reset -> Clearall
+  ^area ${AREA}
+  ^% -> Error "команда не распознана"
+
+EOF
+"#).unwrap();
+        let records = template.parse_text("router r1\nrouter r2\narea 0\nreset\nrouter r3\narea 1").unwrap();
+        // EOF описано в шаблоне: незаписанная область 1 в конце текста не сохраняется
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["AREA"], text("0"));
+        assert_eq!(records[1]["AREA"], text("0"));
+        assert_eq!(records[2]["AREA"], text("1"));
+
+        assert!(matches!(template.parse_text("% Invalid input"), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert!(TextFsm::new("Value NAME \\S+\n\nStart\n  ^${NAME}\n").is_err());
+        assert!(TextFsm::new("Value NAME (\\S+)\n\nMain\n  ^${NAME}\n").is_err());
+        assert!(TextFsm::new("Value NAME (\\S+)\n\nStart\n  ^${OTHER}\n").is_err());
+        assert!(TextFsm::new("Value NAME (\\S+)\n\nStart\n  ^${NAME} -> Missing\n").is_err());
+        assert!(TextFsm::new("Value NAME (\\S+)\n\nStart\n  ^${NAME} -> Continue Start\n").is_err());
+    }
+}
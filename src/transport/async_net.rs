@@ -0,0 +1,281 @@
+use crate::error::types::{Error, Result};
+use super::async_traits::AsyncTransport;
+use super::expect::{push_front, take_pending};
+use super::serial::SerialSettings;
+use super::telnet::{escape_iac, ComPortState, TelnetParser};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+async fn connect_tcp(host: &str, port: u16, timeout: Duration) -> Result<TcpStream> {
+    let stream = tokio::time::timeout(timeout, TcpStream::connect((host, port)))
+        .await
+        .map_err(|_| Error::Connection(format!("{}:{}: таймаут подключения", host, port)))?
+        .map_err(|e| Error::Connection(format!("{}:{}: {}", host, port, e)))?;
+    stream.set_nodelay(true)?;
+    Ok(stream)
+}
+
+/// Асинхронный вариант TcpTransport (raw TCP порт консольного сервера)
+pub struct AsyncTcpTransport {
+    host: String,
+    port: u16,
+    timeout: Duration,
+    stream: Option<TcpStream>,
+    pending: VecDeque<u8>,
+}
+
+impl AsyncTcpTransport {
+    pub fn new(host: String, port: u16) -> Self {
+        Self {
+            host,
+            port,
+            timeout: Duration::from_secs(10),
+            stream: None,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncTransport for AsyncTcpTransport {
+    async fn connect(&mut self) -> Result<()> {
+        self.stream = Some(connect_tcp(&self.host, self.port, self.timeout).await?);
+        self.pending.clear();
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.shutdown().await;
+        }
+        Ok(())
+    }
+
+    async fn send(&mut self, data: &[u8]) -> Result<()> {
+        match &mut self.stream {
+            Some(stream) => {
+                stream.write_all(data).await?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    async fn read_chunk(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if !self.pending.is_empty() {
+            return Ok(take_pending(&mut self.pending, buf));
+        }
+
+        match &mut self.stream {
+            Some(stream) => match stream.read(buf).await? {
+                0 => Err(Error::Connection(format!("Connection closed by {}:{}", self.host, self.port))),
+                bytes_read => Ok(bytes_read),
+            },
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+/// Асинхронный вариант TelnetTransport: то же согласование опций, включая RFC 2217
+pub struct AsyncTelnetTransport {
+    host: String,
+    port: u16,
+    timeout: Duration,
+    terminal_type: String,
+    window_size: (u16, u16),
+    com_port_baud_rate: Option<u32>,
+    com_port_settings: SerialSettings,
+    stream: Option<TcpStream>,
+    parser: TelnetParser,
+    pending: VecDeque<u8>,
+}
+
+impl AsyncTelnetTransport {
+    pub fn new(host: String, port: u16) -> Self {
+        let terminal_type = "VT100".to_string();
+        let window_size = (80, 24);
+        Self {
+            host,
+            port,
+            timeout: Duration::from_secs(10),
+            parser: TelnetParser::new(terminal_type.clone(), window_size),
+            terminal_type,
+            window_size,
+            com_port_baud_rate: None,
+            com_port_settings: SerialSettings::default(),
+            stream: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Тип терминала, сообщаемый серверу через TTYPE
+    pub fn with_terminal_type(mut self, terminal_type: &str) -> Self {
+        self.terminal_type = terminal_type.to_string();
+        self
+    }
+
+    /// Размер окна (ширина, высота), сообщаемый серверу через NAWS
+    pub fn with_window_size(mut self, width: u16, height: u16) -> Self {
+        self.window_size = (width, height);
+        self
+    }
+
+    /// Управление удаленным последовательным портом по RFC 2217
+    pub fn with_com_port_baud_rate(mut self, baud_rate: u32) -> Self {
+        self.com_port_baud_rate = Some(baud_rate);
+        self
+    }
+
+    /// Формат кадра удаленного порта RFC 2217 (по умолчанию 8N1)
+    pub fn with_com_port_settings(mut self, settings: SerialSettings) -> Self {
+        self.com_port_settings = settings;
+        self
+    }
+
+    /// Читает порцию данных из сокета, отвечая на согласование опций
+    async fn fill_pending(&mut self) -> Result<()> {
+        let stream = self.stream.as_mut()
+            .ok_or_else(|| Error::Connection("Port not connected".to_string()))?;
+
+        let mut raw = [0u8; 1024];
+        let bytes_read = stream.read(&mut raw).await?;
+        if bytes_read == 0 {
+            return Err(Error::Connection(format!("Connection closed by {}:{}", self.host, self.port)));
+        }
+
+        let mut data = Vec::new();
+        let mut reply = Vec::new();
+        self.parser.feed(&raw[..bytes_read], &mut data, &mut reply);
+        if !reply.is_empty() {
+            stream.write_all(&reply).await?;
+        }
+        self.pending.extend(data);
+        Ok(())
+    }
+
+    async fn negotiate_com_port(&mut self, baud_rate: u32) -> Result<()> {
+        let request = self.parser.request_com_port(baud_rate, self.com_port_settings);
+        if let Some(stream) = self.stream.as_mut() {
+            stream.write_all(&request).await?;
+        }
+
+        let timeout = self.timeout;
+        let (host, port) = (self.host.clone(), self.port);
+        let negotiation = async {
+            loop {
+                match self.parser.com_port_state() {
+                    ComPortState::Acknowledged(_) => return Ok(()),
+                    ComPortState::Refused => {
+                        return Err(Error::Connection(format!(
+                            "{}:{}: сервер не поддерживает RFC 2217 (COM-PORT-OPTION)", self.host, self.port
+                        )));
+                    }
+                    _ => self.fill_pending().await?,
+                }
+            }
+        };
+
+        tokio::time::timeout(timeout, negotiation).await.map_err(|_| Error::Connection(format!(
+            "{}:{}: сервер не подтвердил скорость {} бод", host, port, baud_rate
+        )))?
+    }
+}
+
+#[async_trait]
+impl AsyncTransport for AsyncTelnetTransport {
+    async fn connect(&mut self) -> Result<()> {
+        self.stream = Some(connect_tcp(&self.host, self.port, self.timeout).await?);
+        self.parser = TelnetParser::new(self.terminal_type.clone(), self.window_size);
+        self.pending.clear();
+
+        if let Some(baud_rate) = self.com_port_baud_rate {
+            if let Err(e) = self.negotiate_com_port(baud_rate).await {
+                self.stream = None;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        if let Some(mut stream) = self.stream.take() {
+            let _ = stream.shutdown().await;
+        }
+        Ok(())
+    }
+
+    async fn send(&mut self, data: &[u8]) -> Result<()> {
+        match &mut self.stream {
+            Some(stream) => {
+                stream.write_all(&escape_iac(data)).await?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    async fn read_chunk(&mut self, buf: &mut [u8]) -> Result<usize> {
+        // Порция может состоять только из согласования опций - читаем до полезных данных
+        while self.pending.is_empty() {
+            self.fill_pending().await?;
+        }
+        Ok(take_pending(&mut self.pending, buf))
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::telnet::{DO, IAC, OPT_ECHO, WILL};
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_async_telnet_negotiation() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            socket.write_all(&[IAC, WILL, OPT_ECHO]).await.unwrap();
+            socket.write_all(b"esr-200# ").await.unwrap();
+
+            let mut reply = [0u8; 3];
+            socket.read_exact(&mut reply).await.unwrap();
+            reply
+        });
+
+        let mut transport = AsyncTelnetTransport::new("127.0.0.1".to_string(), port);
+        transport.connect().await.unwrap();
+        assert_eq!(transport.receive_until_prompt("esr-200#").await.unwrap(), "esr-200# ");
+        assert_eq!(server.await.unwrap(), [IAC, DO, OPT_ECHO]);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::error::types::{Error, Result};
+use super::expect::{compile_patterns, match_buffer, push_front, take_pending, ExpectMatch};
+use super::traits::Transport;
+use async_trait::async_trait;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Асинхронный транспорт для tokio. Операции можно отменить, удалив future;
+/// таймауты ожидания - обычные таймеры tokio, без занятых потоков
+#[async_trait]
+pub trait AsyncTransport: Send {
+    async fn connect(&mut self) -> Result<()>;
+    async fn disconnect(&mut self) -> Result<()>;
+    async fn send(&mut self, data: &[u8]) -> Result<()>;
+    /// Ждет и читает очередную порцию данных; отмена не теряет данные
+    async fn read_chunk(&mut self, buf: &mut [u8]) -> Result<usize>;
+    /// Возвращает данные, чтобы следующий read_chunk отдал их первыми
+    fn unread(&mut self, data: &[u8]);
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()>;
+    fn timeout(&self) -> Duration;
+
+    async fn send_string(&mut self, data: &str) -> Result<()> {
+        let mut buffer = data.as_bytes().to_vec();
+        buffer.extend_from_slice(b"\r\n");
+        self.send(&buffer).await
+    }
+
+    /// Ожидает первое совпадение одного из шаблонов.
+    /// По истечении `timeout` возвращает `Error::Timeout` с накопленными данными
+    async fn expect(&mut self, patterns: &[Regex], timeout: Duration) -> Result<ExpectMatch> {
+        let byte_patterns = compile_patterns(patterns)?;
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut buffer = Vec::new();
+        let mut chunk = [0u8; 1024];
+
+        loop {
+            if let Some((found, end)) = match_buffer(&byte_patterns, &buffer) {
+                if end < buffer.len() {
+                    self.unread(&buffer[end..]);
+                }
+                return Ok(found);
+            }
+
+            match tokio::time::timeout_at(deadline, self.read_chunk(&mut chunk)).await {
+                Ok(Ok(bytes_read)) => buffer.extend_from_slice(&chunk[..bytes_read]),
+                Ok(Err(Error::Timeout(_))) | Err(_) => {
+                    return Err(Error::Timeout(String::from_utf8_lossy(&buffer).to_string()));
+                }
+                Ok(Err(e)) => return Err(e),
+            }
+        }
+    }
+
+    /// Читает до промпта (точное совпадение текста) с таймаутом транспорта
+    async fn receive_until_prompt(&mut self, prompt: &str) -> Result<String> {
+        let pattern = Regex::new(&format!("{}[ \\t]*", regex::escape(prompt)))
+            .map_err(|e| Error::ConfigValidation(e.to_string()))?;
+        let timeout = self.timeout();
+        Ok(self.expect(&[pattern], timeout).await?.text())
+    }
+}
+
+#[async_trait]
+impl<T: AsyncTransport + ?Sized> AsyncTransport for Box<T> {
+    async fn connect(&mut self) -> Result<()> {
+        (**self).connect().await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        (**self).disconnect().await
+    }
+
+    async fn send(&mut self, data: &[u8]) -> Result<()> {
+        (**self).send(data).await
+    }
+
+    async fn read_chunk(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (**self).read_chunk(buf).await
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        (**self).unread(data)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        (**self).set_timeout(timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        (**self).timeout()
+    }
+
+    async fn send_string(&mut self, data: &str) -> Result<()> {
+        (**self).send_string(data).await
+    }
+
+    async fn expect(&mut self, patterns: &[Regex], timeout: Duration) -> Result<ExpectMatch> {
+        (**self).expect(patterns, timeout).await
+    }
+
+    async fn receive_until_prompt(&mut self, prompt: &str) -> Result<String> {
+        (**self).receive_until_prompt(prompt).await
+    }
+}
+
+/// Интервал, с которым BlockingTransport опрашивает блокирующий транспорт
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Асинхронная обертка над блокирующим транспортом (serial, SSH, PTY, MockTransport):
+/// каждая операция выполняется в пуле блокирующих задач tokio
+pub struct BlockingTransport<T: Transport + Send + 'static> {
+    inner: Arc<Mutex<T>>,
+    timeout: Duration,
+    /// Прочитанные данные; общие с фоновой задачей, чтобы не терять их при отмене чтения
+    pending: Arc<Mutex<VecDeque<u8>>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl<T: Transport + Send + 'static> BlockingTransport<T> {
+    pub fn new(inner: T) -> Self {
+        let timeout = inner.timeout();
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            timeout,
+            pending: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    async fn run<R, F>(&self, operation: F) -> Result<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut T) -> Result<R> + Send + 'static,
+    {
+        let inner = Arc::clone(&self.inner);
+        tokio::task::spawn_blocking(move || {
+            operation(&mut lock(&inner))
+        })
+        .await
+        .map_err(|e| Error::Connection(format!("Фоновая операция транспорта не завершилась: {}", e)))?
+    }
+}
+
+#[async_trait]
+impl<T: Transport + Send + 'static> AsyncTransport for BlockingTransport<T> {
+    async fn connect(&mut self) -> Result<()> {
+        lock(&self.pending).clear();
+        let timeout = self.timeout;
+        self.run(move |transport| {
+            transport.connect()?;
+            transport.set_timeout(timeout)
+        }).await
+    }
+
+    async fn disconnect(&mut self) -> Result<()> {
+        self.run(|transport| transport.disconnect()).await
+    }
+
+    async fn send(&mut self, data: &[u8]) -> Result<()> {
+        let data = data.to_vec();
+        self.run(move |transport| transport.send(&data)).await
+    }
+
+    async fn read_chunk(&mut self, buf: &mut [u8]) -> Result<usize> {
+        // Короткие блокирующие чтения, чтобы отмена future срабатывала быстро
+        let capacity = buf.len();
+        loop {
+            {
+                let mut pending = lock(&self.pending);
+                if !pending.is_empty() {
+                    return Ok(take_pending(&mut pending, buf));
+                }
+            }
+
+            let pending = Arc::clone(&self.pending);
+            self.run(move |transport| {
+                let mut chunk = vec![0u8; capacity];
+                let bytes_read = transport.read_chunk(&mut chunk, POLL_INTERVAL)?;
+                lock(&pending).extend(&chunk[..bytes_read]);
+                Ok(())
+            }).await?;
+        }
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut lock(&self.pending), data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        // Применяется к блокирующему транспорту при подключении
+        self.timeout = timeout;
+        Ok(())
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+/// Блокирующий транспорт поверх асинхронного: синхронные драйверы устройств работают
+/// с сетевыми транспортами tokio. Каждая операция выполняется через `Handle::block_on`,
+/// поэтому вызывать его можно только вне рабочих потоков рантайма (в `spawn_blocking`)
+pub struct BlockOnTransport<T: AsyncTransport> {
+    inner: T,
+    runtime: tokio::runtime::Handle,
+}
+
+impl<T: AsyncTransport> BlockOnTransport<T> {
+    /// Обертка, выполняющая операции на рантайме `runtime`
+    pub fn new(inner: T, runtime: tokio::runtime::Handle) -> Self {
+        Self { inner, runtime }
+    }
+}
+
+impl<T: AsyncTransport> Transport for BlockOnTransport<T> {
+    fn connect(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.connect())
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.runtime.block_on(self.inner.disconnect())
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        self.runtime.block_on(self.inner.send(data))
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        // read_chunk асинхронного транспорта не теряет данные при отмене по таймауту
+        match self.runtime.block_on(tokio::time::timeout(wait, self.inner.read_chunk(buf))) {
+            Ok(result) => result,
+            Err(_) => Ok(0),
+        }
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        self.inner.unread(data)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.inner.timeout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[tokio::test]
+    async fn test_blocking_adapter_expect() {
+        let mock = MockTransport::new()
+            .respond("login: ")
+            .expect_command("admin")
+            .respond_chunks(&["Pass", "word: "]);
+        let handle = mock.handle();
+
+        let mut transport = BlockingTransport::new(mock);
+        transport.connect().await.unwrap();
+        assert_eq!(transport.receive_until_prompt("login:").await.unwrap(), "login: ");
+        transport.send_string("admin").await.unwrap();
+        assert_eq!(transport.receive_until_prompt("Password:").await.unwrap(), "Password: ");
+
+        match transport.receive_until_prompt("#").await {
+            Err(Error::Timeout(output)) => assert_eq!(output, ""),
+            other => panic!("ожидался таймаут, получено {:?}", other),
+        }
+        handle.verify().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_block_on_adapter_expect() {
+        let mock = MockTransport::new()
+            .respond("login: ")
+            .expect_command("admin")
+            .respond_chunks(&["Pass", "word: "]);
+        let handle = mock.handle();
+
+        let mut transport = BlockOnTransport::new(BlockingTransport::new(mock), tokio::runtime::Handle::current());
+        tokio::task::spawn_blocking(move || {
+            transport.connect().unwrap();
+            assert_eq!(transport.receive_until_prompt("login:").unwrap(), "login: ");
+            transport.send_string("admin").unwrap();
+            assert_eq!(transport.receive_until_prompt("Password:").unwrap(), "Password: ");
+        })
+        .await
+        .unwrap();
+        handle.verify().unwrap();
+    }
+}
@@ -0,0 +1,361 @@
+use crate::error::types::{Error, Result};
+use super::serial::SerialTransport;
+use super::traits::Transport;
+use regex::Regex;
+use serialport::SerialPortType;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+/// Скорости, которые перебирает автоопределение (в порядке распространенности)
+pub const COMMON_BAUD_RATES: &[u32] = &[115200, 9600, 38400, 57600, 19200];
+
+/// Время ожидания ответа на каждой скорости при автоопределении
+pub const AUTO_BAUD_PROBE: Duration = Duration::from_millis(500);
+
+/// Минимальная оценка, при которой ответ считается читаемым
+const MIN_SCORE: u32 = 90;
+
+/// Бонус за промпт или приглашение ко входу в ответе
+const PROMPT_BONUS: u32 = 100;
+
+/// Промпт или приглашение ко входу в конце ответа
+static PROMPT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)([\w.\-()@:~/\[\]]+\s?[#>$%]|login:|username:|password:)\s*$").expect("корректный шаблон")
+});
+
+/// USB атрибуты адаптера
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsbInfo {
+    pub vid: u16,
+    pub pid: u16,
+    pub serial_number: Option<String>,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    /// Расположение в дереве USB (например, "1-1.2"), не меняется при перезагрузке
+    pub location: Option<String>,
+}
+
+/// Последовательный порт, найденный в системе
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortInfo {
+    /// Путь к устройству (/dev/ttyUSB0, COM3)
+    pub name: String,
+    /// Заполняется только для USB адаптеров
+    pub usb: Option<UsbInfo>,
+}
+
+impl PortInfo {
+    /// Тип порта для вывода пользователю
+    pub fn kind(&self) -> &'static str {
+        if self.usb.is_some() { "usb" } else { "serial" }
+    }
+}
+
+/// Список последовательных портов системы
+pub fn available_ports() -> Result<Vec<PortInfo>> {
+    let mut ports: Vec<PortInfo> = serialport::available_ports()?
+        .into_iter()
+        .map(|port| PortInfo {
+            usb: match port.port_type {
+                SerialPortType::UsbPort(usb) => Some(UsbInfo {
+                    vid: usb.vid,
+                    pid: usb.pid,
+                    serial_number: usb.serial_number,
+                    manufacturer: usb.manufacturer,
+                    product: usb.product,
+                    location: usb_location(&port.port_name),
+                }),
+                _ => None,
+            },
+            name: port.port_name,
+        })
+        .collect();
+    ports.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(ports)
+}
+
+/// Расположение USB порта из sysfs: ближайший к tty узел вида "1-1.2"
+#[cfg(target_os = "linux")]
+fn usb_location(port_name: &str) -> Option<String> {
+    let tty = std::path::Path::new(port_name).file_name()?.to_str()?;
+    let device = std::fs::canonicalize(format!("/sys/class/tty/{}/device", tty)).ok()?;
+    device.ancestors()
+        .filter_map(|path| path.file_name()?.to_str())
+        .find(|name| {
+            // Узел порта: номер шины, дефис, цепочка портов через точку (без ":" интерфейса)
+            name.split_once('-').is_some_and(|(bus, ports)| {
+                !bus.is_empty() && bus.chars().all(|c| c.is_ascii_digit())
+                    && !ports.is_empty() && ports.chars().all(|c| c.is_ascii_digit() || c == '.')
+            })
+        })
+        .map(str::to_string)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn usb_location(_port_name: &str) -> Option<String> {
+    None
+}
+
+/// Выбор USB адаптера по атрибутам вместо пути к устройству:
+/// `usb:serial=A50285BI`, `usb:vid=0403,pid=6001`, `usb:0403:6001`, `usb:location=1-1.2`.
+/// Условия через запятую объединяются по "и"
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsbSelector {
+    pub serial_number: Option<String>,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub location: Option<String>,
+}
+
+impl UsbSelector {
+    pub const PREFIX: &'static str = "usb:";
+
+    /// Является ли `host` селектором USB адаптера
+    pub fn is_selector(host: &str) -> bool {
+        host.starts_with(Self::PREFIX)
+    }
+
+    pub fn parse(host: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::ConfigValidation(format!("Некорректный селектор '{}': {}", host, reason));
+        let body = host.strip_prefix(Self::PREFIX).ok_or_else(|| invalid("ожидается префикс usb:"))?;
+        let parse_id = |value: &str| u16::from_str_radix(value, 16).map_err(|_| invalid("VID/PID - 4 шестнадцатеричные цифры"));
+
+        let mut selector = UsbSelector::default();
+        for part in body.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            match part.split_once('=') {
+                Some(("serial", value)) => selector.serial_number = Some(value.to_string()),
+                Some(("vid", value)) => selector.vid = Some(parse_id(value)?),
+                Some(("pid", value)) => selector.pid = Some(parse_id(value)?),
+                Some(("location", value)) => selector.location = Some(value.to_string()),
+                Some((key, _)) => return Err(invalid(&format!("неизвестный ключ '{}'", key))),
+                // Короткая запись VID:PID
+                None => match part.split_once(':') {
+                    Some((vid, pid)) => {
+                        selector.vid = Some(parse_id(vid)?);
+                        selector.pid = Some(parse_id(pid)?);
+                    }
+                    None => return Err(invalid(&format!("ожидается ключ=значение, получено '{}'", part))),
+                },
+            }
+        }
+
+        if selector == UsbSelector::default() {
+            return Err(invalid("не задано ни одного условия"));
+        }
+        Ok(selector)
+    }
+
+    pub fn matches(&self, port: &PortInfo) -> bool {
+        let Some(usb) = &port.usb else {
+            return false;
+        };
+        self.serial_number.as_ref().is_none_or(|serial| usb.serial_number.as_ref() == Some(serial))
+            && self.vid.is_none_or(|vid| usb.vid == vid)
+            && self.pid.is_none_or(|pid| usb.pid == pid)
+            && self.location.as_ref().is_none_or(|location| usb.location.as_ref() == Some(location))
+    }
+
+    /// Путь к единственному подходящему порту
+    pub fn resolve(&self, ports: &[PortInfo]) -> Result<String> {
+        let found: Vec<&PortInfo> = ports.iter().filter(|port| self.matches(port)).collect();
+        match found.as_slice() {
+            [port] => Ok(port.name.clone()),
+            [] => Err(Error::Connection(format!("USB адаптер {} не найден", self))),
+            _ => Err(Error::Connection(format!(
+                "USB адаптер {} неоднозначен, подходят: {} (уточните serial, vid/pid или location)",
+                self,
+                found.iter().map(|port| port.name.as_str()).collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for UsbSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(serial) = &self.serial_number {
+            parts.push(format!("serial={}", serial));
+        }
+        if let Some(vid) = self.vid {
+            parts.push(format!("vid={:04x}", vid));
+        }
+        if let Some(pid) = self.pid {
+            parts.push(format!("pid={:04x}", pid));
+        }
+        if let Some(location) = &self.location {
+            parts.push(format!("location={}", location));
+        }
+        write!(f, "{}{}", Self::PREFIX, parts.join(","))
+    }
+}
+
+/// Оценивает, насколько ответ похож на вывод консоли: доля печатных символов (0-100)
+/// плюс бонус, если ответ заканчивается промптом или приглашением ко входу.
+/// На неверной скорости консоль выдает в основном управляющие и старшие байты
+pub fn score_response(data: &[u8]) -> u32 {
+    if data.is_empty() {
+        return 0;
+    }
+
+    let readable = data.iter()
+        .filter(|&&b| b.is_ascii_graphic() || matches!(b, b' ' | b'\r' | b'\n' | b'\t'))
+        .count();
+    let mut score = (readable * 100 / data.len()) as u32;
+
+    if score >= MIN_SCORE && PROMPT.is_match(&String::from_utf8_lossy(data)) {
+        score += PROMPT_BONUS;
+    }
+    score
+}
+
+/// Перебирает скорости на открытом порту и оставляет ту, на которой ответ читаемый.
+/// Возвращает выбранную скорость
+pub fn detect_baud_rate(transport: &mut SerialTransport, rates: &[u32], probe: Duration) -> Result<u32> {
+    let rate = detect_with(transport, rates, probe, |transport, rate| transport.set_baud_rate(rate))?;
+    transport.set_baud_rate(rate)?;
+    Ok(rate)
+}
+
+fn detect_with<T, F>(transport: &mut T, rates: &[u32], probe: Duration, mut set_rate: F) -> Result<u32>
+where
+    T: Transport + ?Sized,
+    F: FnMut(&mut T, u32) -> Result<()>,
+{
+    let mut best: Option<(u32, u32)> = None;
+
+    for &rate in rates {
+        set_rate(transport, rate)?;
+        let score = score_response(&probe_response(transport, probe)?);
+
+        // Промпт на читаемом ответе - дальше можно не искать
+        if score >= MIN_SCORE + PROMPT_BONUS {
+            return Ok(rate);
+        }
+        if best.is_none_or(|(best_score, _)| score > best_score) {
+            best = Some((score, rate));
+        }
+    }
+
+    match best {
+        Some((score, rate)) if score >= MIN_SCORE => Ok(rate),
+        _ => Err(Error::Connection(format!(
+            "Не удалось определить скорость порта: нет читаемого ответа на скоростях {:?}", rates
+        ))),
+    }
+}
+
+/// Отправляет Enter и собирает ответ в течение `probe`
+fn probe_response<T: Transport + ?Sized>(transport: &mut T, probe: Duration) -> Result<Vec<u8>> {
+    transport.send(b"\r\n")?;
+
+    let deadline = Instant::now() + probe;
+    let mut response = Vec::new();
+    let mut chunk = [0u8; 256];
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        match transport.read_chunk(&mut chunk, deadline - now) {
+            Ok(bytes_read) => response.extend_from_slice(&chunk[..bytes_read]),
+            Err(Error::Timeout(_)) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    fn usb_port(name: &str, serial: &str, location: &str) -> PortInfo {
+        PortInfo {
+            name: name.to_string(),
+            usb: Some(UsbInfo {
+                vid: 0x0403,
+                pid: 0x6001,
+                serial_number: Some(serial.to_string()),
+                manufacturer: None,
+                product: None,
+                location: Some(location.to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_usb_selector_parse() {
+        let selector = UsbSelector::parse("usb:serial=A50285BI").unwrap();
+        assert_eq!(selector.serial_number.as_deref(), Some("A50285BI"));
+
+        let selector = UsbSelector::parse("usb:0403:6001,location=1-1.2").unwrap();
+        assert_eq!((selector.vid, selector.pid), (Some(0x0403), Some(0x6001)));
+        assert_eq!(selector.to_string(), "usb:vid=0403,pid=6001,location=1-1.2");
+
+        assert!(UsbSelector::parse("usb:").is_err());
+        assert!(UsbSelector::parse("usb:vid=xyz").is_err());
+        assert!(UsbSelector::parse("usb:port=1").is_err());
+    }
+
+    #[test]
+    fn test_usb_selector_resolve() {
+        let ports = vec![
+            PortInfo { name: "/dev/ttyS0".to_string(), usb: None },
+            usb_port("/dev/ttyUSB0", "A50285BI", "1-1.1"),
+            usb_port("/dev/ttyUSB1", "B7731XQ2", "1-1.2"),
+        ];
+
+        let by_serial = UsbSelector::parse("usb:serial=B7731XQ2").unwrap();
+        assert_eq!(by_serial.resolve(&ports).unwrap(), "/dev/ttyUSB1");
+
+        let by_location = UsbSelector::parse("usb:location=1-1.1").unwrap();
+        assert_eq!(by_location.resolve(&ports).unwrap(), "/dev/ttyUSB0");
+
+        let missing = UsbSelector::parse("usb:serial=NOPE").unwrap().resolve(&ports).unwrap_err();
+        assert!(missing.to_string().contains("не найден"));
+
+        let ambiguous = UsbSelector::parse("usb:vid=0403,pid=6001").unwrap().resolve(&ports).unwrap_err();
+        assert!(ambiguous.to_string().contains("/dev/ttyUSB0, /dev/ttyUSB1"));
+    }
+
+    #[test]
+    fn test_score_response() {
+        assert_eq!(score_response(b""), 0);
+        assert!(score_response(b"\xf8\x00\xe0\x80\xfe~") < MIN_SCORE);
+        assert_eq!(score_response(b"\r\nSome banner\r\n"), 100);
+        assert!(score_response(b"\r\nesr-200# ") >= MIN_SCORE + PROMPT_BONUS);
+        assert!(score_response(b"\r\nUser Name: \r\nlogin: ") >= MIN_SCORE + PROMPT_BONUS);
+    }
+
+    #[test]
+    fn test_detect_picks_readable_rate() {
+        // На 115200 мусор, на 9600 - промпт
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .respond("\u{fffd}\u{fffd}\x00")
+            .expect_bytes("\r\n")
+            .respond("\r\nswitch> ");
+        mock.connect().unwrap();
+
+        let mut tried = Vec::new();
+        let rate = detect_with(&mut mock, COMMON_BAUD_RATES, Duration::from_millis(50), |_, rate| {
+            tried.push(rate);
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(rate, 9600);
+        assert_eq!(tried, vec![115200, 9600]);
+    }
+
+    #[test]
+    fn test_detect_fails_without_response() {
+        let mut mock = MockTransport::new()
+            .expect_bytes("\r\n")
+            .expect_bytes("\r\n");
+        mock.connect().unwrap();
+
+        let result = detect_with(&mut mock, &[115200, 9600], Duration::from_millis(10), |_, _| Ok(()));
+        assert!(matches!(result, Err(Error::Connection(_))));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::types::{Error, Result};
+use super::traits::Transport;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Результат ожидания одного из шаблонов
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectMatch {
+    /// Индекс сработавшего шаблона
+    pub index: usize,
+    /// Текст до совпадения
+    pub before: String,
+    /// Совпавший текст
+    pub matched: String,
+}
+
+impl ExpectMatch {
+    /// Весь прочитанный текст, включая совпадение
+    pub fn text(&self) -> String {
+        format!("{}{}", self.before, self.matched)
+    }
+}
+
+/// Раньше всех начинающееся совпадение; при равенстве побеждает шаблон с меньшим индексом
+fn find_earliest(patterns: &[regex::bytes::Regex], buffer: &[u8]) -> Option<(usize, usize, usize)> {
+    patterns.iter()
+        .enumerate()
+        .filter_map(|(index, re)| re.find(buffer).map(|m| (m.start(), index, m.end())))
+        .min()
+        .map(|(start, index, end)| (index, start, end))
+}
+
+/// Шаблоны для поиска по байтам: границы совпадения не сдвигаются из-за невалидного UTF-8
+pub(crate) fn compile_patterns(patterns: &[Regex]) -> Result<Vec<regex::bytes::Regex>> {
+    patterns.iter()
+        .map(|re| regex::bytes::Regex::new(re.as_str()))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::ConfigValidation(format!("Некорректный шаблон: {}", e)))
+}
+
+/// Ищет совпадение в накопленном буфере; вместе с результатом возвращает
+/// позицию конца совпадения (данные после нее нужно вернуть транспорту)
+pub(crate) fn match_buffer(patterns: &[regex::bytes::Regex], buffer: &[u8]) -> Option<(ExpectMatch, usize)> {
+    find_earliest(patterns, buffer).map(|(index, start, end)| {
+        let found = ExpectMatch {
+            index,
+            before: String::from_utf8_lossy(&buffer[..start]).to_string(),
+            matched: String::from_utf8_lossy(&buffer[start..end]).to_string(),
+        };
+        (found, end)
+    })
+}
+
+/// Читает данные, пока не совпадет один из шаблонов или не истечет таймаут.
+/// Данные после совпадения возвращаются транспорту через `unread`
+pub(crate) fn expect<T: Transport + ?Sized>(
+    transport: &mut T,
+    patterns: &[Regex],
+    timeout: Duration,
+) -> Result<ExpectMatch> {
+    let byte_patterns = compile_patterns(patterns)?;
+
+    let deadline = Instant::now() + timeout;
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    loop {
+        if let Some((found, end)) = match_buffer(&byte_patterns, &buffer) {
+            if end < buffer.len() {
+                transport.unread(&buffer[end..]);
+            }
+            return Ok(found);
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(Error::Timeout(String::from_utf8_lossy(&buffer).to_string()));
+        }
+
+        match transport.read_chunk(&mut chunk, deadline - now) {
+            Ok(bytes_read) => buffer.extend_from_slice(&chunk[..bytes_read]),
+            // Транспорт знает, что данных больше не будет (например, MockTransport)
+            Err(Error::Timeout(_)) => {
+                return Err(Error::Timeout(String::from_utf8_lossy(&buffer).to_string()));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Читает данные до разделителя включительно
+pub(crate) fn receive_until<T: Transport + ?Sized>(
+    transport: &mut T,
+    delimiter: u8,
+    timeout: Duration,
+) -> Result<Vec<u8>> {
+    let deadline = Instant::now() + timeout;
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(Error::Timeout(String::from_utf8_lossy(&buffer).to_string()));
+        }
+
+        let bytes_read = match transport.read_chunk(&mut chunk, deadline - now) {
+            Ok(bytes_read) => bytes_read,
+            Err(Error::Timeout(_)) => {
+                return Err(Error::Timeout(String::from_utf8_lossy(&buffer).to_string()));
+            }
+            Err(e) => return Err(e),
+        };
+
+        let data = &chunk[..bytes_read];
+        if let Some(pos) = data.iter().position(|&b| b == delimiter) {
+            buffer.extend_from_slice(&data[..=pos]);
+            if pos + 1 < data.len() {
+                transport.unread(&data[pos + 1..]);
+            }
+            return Ok(buffer);
+        }
+        buffer.extend_from_slice(data);
+    }
+}
+
+/// Отдает ранее возвращенные через `unread` данные; для реализаций `read_chunk`
+pub(crate) fn take_pending(pending: &mut VecDeque<u8>, buf: &mut [u8]) -> usize {
+    let count = pending.len().min(buf.len());
+    for (slot, byte) in buf.iter_mut().zip(pending.drain(..count)) {
+        *slot = byte;
+    }
+    count
+}
+
+/// Возвращает данные в начало очереди чтения
+pub(crate) fn push_front(pending: &mut VecDeque<u8>, data: &[u8]) {
+    for &byte in data.iter().rev() {
+        pending.push_front(byte);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MockTransport;
+
+    #[test]
+    fn test_expect_multiple_patterns() {
+        let mut mock = MockTransport::new()
+            .respond_chunks(&["Username", ": "]);
+        mock.connect().unwrap();
+
+        let patterns = [
+            Regex::new(r"(?i)password:\s*$").unwrap(),
+            Regex::new(r"(?i)username:\s*$").unwrap(),
+        ];
+        let result = mock.expect(&patterns, Duration::from_secs(1)).unwrap();
+        assert_eq!(result.index, 1);
+        assert_eq!(result.before, "");
+        assert_eq!(result.matched, "Username: ");
+    }
+
+    #[test]
+    fn test_expect_keeps_data_after_match() {
+        let mut mock = MockTransport::new()
+            .respond("line 1\r\nesr-200# line 2\r\nesr-200# ");
+        mock.connect().unwrap();
+
+        let prompt = [Regex::new(r"esr-200# ").unwrap()];
+        let first = mock.expect(&prompt, Duration::from_secs(1)).unwrap();
+        assert_eq!(first.before, "line 1\r\n");
+
+        let second = mock.expect(&prompt, Duration::from_secs(1)).unwrap();
+        assert_eq!(second.before, "line 2\r\n");
+    }
+
+    #[test]
+    fn test_expect_timeout_returns_buffer() {
+        let mut mock = MockTransport::new()
+            .respond("partial output")
+            .expect_command("never");
+        mock.connect().unwrap();
+
+        let prompt = [Regex::new(r"esr-200#").unwrap()];
+        match mock.expect(&prompt, Duration::from_secs(1)) {
+            Err(Error::Timeout(buffer)) => assert_eq!(buffer, "partial output"),
+            other => panic!("ожидался таймаут, получено {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_receive_until_prompt_is_literal() {
+        let mut mock = MockTransport::new().respond("router(config)# ");
+        mock.connect().unwrap();
+
+        // receive_until_prompt экранирует спецсимволы регулярных выражений
+        let text = mock.receive_until_prompt("router(config)#").unwrap();
+        assert_eq!(text, "router(config)# ");
+    }
+}
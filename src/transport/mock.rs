@@ -0,0 +1,333 @@
+use crate::error::types::{Error, Result};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Таймаут по умолчанию, пока он не задан через `set_timeout`
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Шаг сценария MockTransport
+#[derive(Debug, Clone)]
+pub enum MockStep {
+    /// Ожидать от клиента ровно эти байты
+    Expect(Vec<u8>),
+    /// Выдать клиенту данные (одним чтением)
+    Respond(Vec<u8>),
+    /// Пауза перед следующими шагами
+    Delay(Duration),
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    script: VecDeque<MockStep>,
+    /// Данные, готовые к чтению; каждый элемент - отдельная порция
+    output: VecDeque<Vec<u8>>,
+    /// Отправленные, но еще не сопоставленные с Expect данные
+    input: Vec<u8>,
+    sent: Vec<u8>,
+    failures: Vec<String>,
+    connected: bool,
+    timeout: Option<Duration>,
+}
+
+impl MockState {
+    /// Продвигает сценарий: выдает ответы и паузы до ближайшего Expect,
+    /// сопоставляя его с накопленным вводом
+    fn advance(&mut self) -> Result<()> {
+        loop {
+            match self.script.front() {
+                Some(MockStep::Respond(_)) => {
+                    if let Some(MockStep::Respond(data)) = self.script.pop_front() {
+                        self.output.push_back(data);
+                    }
+                }
+                Some(MockStep::Delay(delay)) => {
+                    // Паузу выдерживаем только когда клиенту больше нечего читать
+                    if !self.output.is_empty() {
+                        return Ok(());
+                    }
+                    std::thread::sleep(*delay);
+                    self.script.pop_front();
+                }
+                Some(MockStep::Expect(expected)) => {
+                    let common = expected.len().min(self.input.len());
+                    if expected[..common] != self.input[..common] {
+                        let failure = format!(
+                            "ожидалось {:?}, получено {:?}",
+                            String::from_utf8_lossy(expected),
+                            String::from_utf8_lossy(&self.input)
+                        );
+                        self.failures.push(failure.clone());
+                        self.input.clear();
+                        return Err(Error::Connection(format!("MockTransport: {}", failure)));
+                    }
+                    if common < expected.len() {
+                        return Ok(());
+                    }
+                    self.input.drain(..common);
+                    self.script.pop_front();
+                }
+                None => {
+                    if !self.input.is_empty() {
+                        let failure = format!(
+                            "неожиданные данные после конца сценария: {:?}",
+                            String::from_utf8_lossy(&self.input)
+                        );
+                        self.failures.push(failure.clone());
+                        self.input.clear();
+                        return Err(Error::Connection(format!("MockTransport: {}", failure)));
+                    }
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Следующая порция данных для чтения, None - данных больше не будет без ввода клиента
+    fn next_chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        if self.output.is_empty() {
+            self.advance()?;
+        }
+        Ok(self.output.pop_front())
+    }
+}
+
+/// Транспорт для тестов без оборудования: работает по сценарию из ожидаемого ввода
+/// и готовых ответов, записывает все отправленное
+///
+/// ```
+/// use rackit::transport::{MockTransport, Transport};
+///
+/// let mut mock = MockTransport::new()
+///     .respond("esr-200# ")
+///     .expect_command("show clock")
+///     .respond_chunks(&["12:00:00\r\n", "esr-200# "]);
+/// let handle = mock.handle();
+///
+/// mock.connect().unwrap();
+/// assert_eq!(mock.receive_until_prompt("esr-200#").unwrap(), "esr-200# ");
+/// mock.send_string("show clock").unwrap();
+/// assert!(mock.receive_until_prompt("esr-200#").unwrap().contains("12:00:00"));
+/// handle.verify().unwrap();
+/// ```
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+/// Доступ к состоянию MockTransport после передачи транспорта драйверу
+#[derive(Clone)]
+pub struct MockHandle {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self { state: Arc::new(Mutex::new(MockState::default())) }
+    }
+
+    fn push(self, step: MockStep) -> Self {
+        lock(&self.state).script.push_back(step);
+        self
+    }
+
+    /// Ожидать от клиента точные байты
+    pub fn expect_bytes(self, data: &str) -> Self {
+        self.push(MockStep::Expect(data.as_bytes().to_vec()))
+    }
+
+    /// Ожидать команду, отправленную через `send_string` (с "\r\n")
+    pub fn expect_command(self, command: &str) -> Self {
+        self.expect_bytes(&format!("{}\r\n", command))
+    }
+
+    /// Выдать данные одной порцией
+    pub fn respond(self, data: &str) -> Self {
+        self.push(MockStep::Respond(data.as_bytes().to_vec()))
+    }
+
+    /// Выдать данные несколькими порциями (по одной на чтение)
+    pub fn respond_chunks(mut self, chunks: &[&str]) -> Self {
+        for chunk in chunks {
+            self = self.respond(chunk);
+        }
+        self
+    }
+
+    /// Пауза перед выдачей следующих данных
+    pub fn delay(self, delay: Duration) -> Self {
+        self.push(MockStep::Delay(delay))
+    }
+
+    /// Добавить произвольный шаг сценария
+    pub fn step(self, step: MockStep) -> Self {
+        self.push(step)
+    }
+
+    pub fn handle(&self) -> MockHandle {
+        MockHandle { state: Arc::clone(&self.state) }
+    }
+}
+
+impl Default for MockTransport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockHandle {
+    /// Все байты, отправленные клиентом
+    pub fn sent(&self) -> Vec<u8> {
+        lock(&self.state).sent.clone()
+    }
+
+    pub fn sent_string(&self) -> String {
+        String::from_utf8_lossy(&lock(&self.state).sent).to_string()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        lock(&self.state).connected
+    }
+
+    /// Последний таймаут, установленный через `set_timeout`
+    pub fn timeout(&self) -> Option<Duration> {
+        lock(&self.state).timeout
+    }
+
+    /// Проверяет, что сценарий выполнен полностью и без расхождений
+    pub fn verify(&self) -> Result<()> {
+        let state = lock(&self.state);
+        let mut problems = state.failures.clone();
+
+        let unmet: Vec<String> = state.script.iter()
+            .filter_map(|step| match step {
+                MockStep::Expect(data) => Some(format!("{:?}", String::from_utf8_lossy(data))),
+                _ => None,
+            })
+            .collect();
+        if !unmet.is_empty() {
+            problems.push(format!("не выполнены ожидания: {}", unmet.join(", ")));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Connection(format!("MockTransport: {}", problems.join("; "))))
+        }
+    }
+}
+
+fn lock(state: &Mutex<MockState>) -> MutexGuard<'_, MockState> {
+    // Паника в тесте не должна прятать результаты остальных проверок
+    state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl super::traits::Transport for MockTransport {
+    fn connect(&mut self) -> Result<()> {
+        lock(&self.state).connected = true;
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        lock(&self.state).connected = false;
+        Ok(())
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        let mut state = lock(&self.state);
+        if !state.connected {
+            return Err(Error::Connection("Port not connected".to_string()));
+        }
+        state.sent.extend_from_slice(data);
+        state.input.extend_from_slice(data);
+
+        // Ответы пойдут в очередь чтения, поэтому паузы здесь не выдерживаем
+        match state.script.front() {
+            Some(MockStep::Expect(_)) | None => state.advance(),
+            _ => Ok(()),
+        }
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], _wait: Duration) -> Result<usize> {
+        let mut state = lock(&self.state);
+        if !state.connected {
+            return Err(Error::Connection("Port not connected".to_string()));
+        }
+
+        // Когда данных больше нет, реальный транспорт ждал бы таймаута - здесь сообщаем сразу
+        let chunk = state.next_chunk()?.ok_or_else(|| Error::Timeout(String::new()))?;
+        let count = chunk.len().min(buf.len());
+        buf[..count].copy_from_slice(&chunk[..count]);
+        if count < chunk.len() {
+            state.output.push_front(chunk[count..].to_vec());
+        }
+        Ok(count)
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        lock(&self.state).output.push_front(data.to_vec());
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        lock(&self.state).timeout = Some(timeout);
+        Ok(())
+    }
+
+    fn timeout(&self) -> Duration {
+        lock(&self.state).timeout.unwrap_or(DEFAULT_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Transport;
+
+    #[test]
+    fn test_scripted_exchange() {
+        let mut mock = MockTransport::new()
+            .respond("login: ")
+            .expect_command("admin")
+            .respond_chunks(&["Pass", "word: "])
+            .expect_command("secret")
+            .delay(Duration::from_millis(5))
+            .respond("esr-200# ");
+        let handle = mock.handle();
+
+        mock.connect().unwrap();
+        assert_eq!(mock.receive_until_prompt("login:").unwrap(), "login: ");
+        mock.send_string("admin").unwrap();
+        assert_eq!(mock.receive_until_prompt("Password:").unwrap(), "Password: ");
+        mock.send_string("secret").unwrap();
+        assert_eq!(mock.receive_until_prompt("esr-200#").unwrap(), "esr-200# ");
+
+        assert_eq!(handle.sent_string(), "admin\r\nsecret\r\n");
+        handle.verify().unwrap();
+    }
+
+    #[test]
+    fn test_divergent_input_reported() {
+        let mut mock = MockTransport::new().expect_command("show version");
+        let handle = mock.handle();
+
+        mock.connect().unwrap();
+        assert!(mock.send_string("show system").is_err());
+        assert!(handle.verify().is_err());
+    }
+
+    #[test]
+    fn test_unmet_expectations_and_receive_until() {
+        let mut mock = MockTransport::new()
+            .respond("line one\r\nline")
+            .respond(" two\r\n")
+            .expect_command("exit");
+        let handle = mock.handle();
+
+        mock.connect().unwrap();
+        assert_eq!(mock.receive_until(b'\n').unwrap(), b"line one\r\n");
+        assert_eq!(mock.receive_until(b'\n').unwrap(), b"line two\r\n");
+        assert!(matches!(mock.receive_until(b'\n'), Err(Error::Timeout(_))));
+
+        let error = handle.verify().unwrap_err().to_string();
+        assert!(error.contains("exit"));
+    }
+}
@@ -1,7 +1,33 @@
 pub mod traits;
+pub mod expect;
 pub mod serial;
+pub mod discovery;
+pub mod telnet;
+pub mod ssh;
+pub mod tcp;
+pub mod mock;
+pub mod recording;
+#[cfg(unix)]
+pub mod pty;
+#[cfg(feature = "async")]
+pub mod async_traits;
+#[cfg(feature = "async")]
+pub mod async_net;
 
 
 pub use traits::Transport;
-pub use serial::SerialTransport;
-
+pub use expect::ExpectMatch;
+pub use regex::Regex;
+pub use serial::{SerialSettings, SerialTransport};
+pub use discovery::{available_ports, detect_baud_rate, PortInfo, UsbInfo, UsbSelector, COMMON_BAUD_RATES};
+pub use telnet::TelnetTransport;
+pub use ssh::SshTransport;
+pub use tcp::TcpTransport;
+pub use mock::{MockHandle, MockStep, MockTransport};
+pub use recording::{RecordingTransport, ReplayTransport, Transcript};
+#[cfg(unix)]
+pub use pty::PtyTransport;
+#[cfg(feature = "async")]
+pub use async_traits::{AsyncTransport, BlockOnTransport, BlockingTransport};
+#[cfg(feature = "async")]
+pub use async_net::{AsyncTcpTransport, AsyncTelnetTransport};
@@ -0,0 +1,242 @@
+use crate::error::types::{Error, Result};
+use super::expect::{push_front, take_pending};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+/// Локальный процесс в псевдотерминале (virsh console, picocom, эмулятор устройства и т.п.)
+pub struct PtyTransport {
+    command: String,
+    timeout: Duration,
+    window_size: (u16, u16),
+    master: Option<File>,
+    child: Option<Child>,
+    pending: VecDeque<u8>,
+}
+
+impl PtyTransport {
+    /// `command` выполняется через `sh -c`
+    pub fn new(command: String) -> Self {
+        Self {
+            command,
+            timeout: Duration::from_secs(10),
+            window_size: (80, 24),
+            master: None,
+            child: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Размер окна псевдотерминала (ширина, высота)
+    pub fn with_window_size(mut self, width: u16, height: u16) -> Self {
+        self.window_size = (width, height);
+        self
+    }
+
+    fn open_pty(&self) -> Result<(OwnedFd, OwnedFd)> {
+        let (width, height) = self.window_size;
+        let winsize = libc::winsize {
+            ws_row: height,
+            ws_col: width,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+
+        let mut master: libc::c_int = -1;
+        let mut slave: libc::c_int = -1;
+        // SAFETY: указатели на локальные переменные валидны на время вызова
+        let rc = unsafe {
+            libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null(), &winsize)
+        };
+        if rc != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        // SAFETY: openpty вернул два открытых дескриптора, владение передается OwnedFd
+        let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+
+        // Мастер не должен наследоваться процессом устройства (и другими дочерними процессами):
+        // пока он открыт у потомка, EOF после завершения процесса не придет
+        // SAFETY: fcntl над открытым дескриптором
+        if unsafe { libc::fcntl(master.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        // Устройства воспринимают "\r\n" как одно нажатие Enter, а терминал по умолчанию
+        // превратил бы его в две строки: игнорируем CR на входе
+        // SAFETY: termios заполняется tcgetattr перед использованием
+        unsafe {
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(slave.as_raw_fd(), &mut termios) == 0 {
+                termios.c_iflag &= !libc::ICRNL;
+                termios.c_iflag |= libc::IGNCR;
+                libc::tcsetattr(slave.as_raw_fd(), libc::TCSANOW, &termios);
+            }
+        }
+
+        Ok((master, slave))
+    }
+
+    /// Читает порцию данных из псевдотерминала, ожидая не дольше `wait`
+    fn read_master(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        let master = self.master.as_mut()
+            .ok_or_else(|| Error::Connection("Port not connected".to_string()))?;
+
+        let mut poll_fd = libc::pollfd { fd: master.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+        let timeout_ms = wait.as_millis().min(libc::c_int::MAX as u128) as libc::c_int;
+        // SAFETY: передаем один корректно инициализированный pollfd
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) };
+        if ready < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        if ready == 0 {
+            return Ok(0);
+        }
+
+        match master.read(buf) {
+            Ok(0) => Err(Error::Connection(format!("Процесс '{}' завершился", self.command))),
+            Ok(bytes_read) => Ok(bytes_read),
+            // Linux возвращает EIO, когда все дескрипторы slave закрыты
+            Err(e) if e.raw_os_error() == Some(libc::EIO) => {
+                Err(Error::Connection(format!("Процесс '{}' завершился", self.command)))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl super::traits::Transport for PtyTransport {
+    fn connect(&mut self) -> Result<()> {
+        let (master, slave) = self.open_pty()?;
+
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(&self.command)
+            .env("TERM", "vt100")
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+
+        // SAFETY: в дочернем процессе вызываются только async-signal-safe функции
+        unsafe {
+            command.pre_exec(|| {
+                // Новая сессия с псевдотерминалом в качестве управляющего терминала
+                if libc::setsid() < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let child = command.spawn()
+            .map_err(|e| Error::Connection(format!("Не удалось запустить '{}': {}", self.command, e)))?;
+
+        self.master = Some(File::from(master));
+        self.child = Some(child);
+        self.pending.clear();
+        println!("Connected to process '{}'", self.command);
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.master = None;
+        if let Some(mut child) = self.child.take() {
+            if child.try_wait()?.is_none() {
+                let _ = child.kill();
+            }
+            let _ = child.wait();
+            println!("Disconnected from process '{}'", self.command);
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        match &mut self.master {
+            Some(master) => {
+                master.write_all(data)?;
+                master.flush()?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        if !self.pending.is_empty() {
+            return Ok(take_pending(&mut self.pending, buf));
+        }
+        self.read_master(buf, wait)
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = timeout;
+        if self.master.is_none() {
+            return Err(Error::Connection("Port not connected".to_string()));
+        }
+        Ok(())
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl Drop for PtyTransport {
+    fn drop(&mut self) {
+        let _ = super::traits::Transport::disconnect(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Transport;
+
+    #[test]
+    fn test_simulated_cli_process() {
+        let script = r#"printf 'esr-200# '; read cmd; echo "output of $cmd"; printf 'esr-200# '; read cmd"#;
+        let mut transport = PtyTransport::new(script.to_string());
+        transport.connect().unwrap();
+        transport.set_timeout(Duration::from_secs(5)).unwrap();
+
+        assert!(transport.receive_until_prompt("esr-200#").unwrap().contains("esr-200#"));
+
+        transport.send_string("show version").unwrap();
+        let response = transport.receive_until_prompt("esr-200#").unwrap();
+        // Терминал возвращает эхо введенной команды, как настоящее устройство
+        assert!(response.contains("show version"));
+        assert!(response.contains("output of show version"));
+
+        transport.disconnect().unwrap();
+    }
+
+    #[test]
+    fn test_master_closed_on_exec() {
+        let (master, _slave) = PtyTransport::new("true".to_string()).open_pty().unwrap();
+        // SAFETY: fcntl над открытым дескриптором
+        let flags = unsafe { libc::fcntl(master.as_raw_fd(), libc::F_GETFD) };
+        assert_eq!(flags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+    }
+
+    #[test]
+    fn test_process_exit_reported() {
+        let mut transport = PtyTransport::new("echo bye".to_string());
+        transport.connect().unwrap();
+        transport.set_timeout(Duration::from_secs(5)).unwrap();
+
+        assert_eq!(transport.receive_until(b'\n').unwrap(), b"bye\r\n");
+        assert!(matches!(transport.receive_until(b'\n'), Err(Error::Connection(_))));
+    }
+}
@@ -0,0 +1,360 @@
+use crate::error::types::{Error, Result};
+use super::expect::{push_front, take_pending};
+use super::mock::{MockHandle, MockStep, MockTransport};
+use super::traits::Transport;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+// Формат файла записи сессии:
+//
+//   # rackit transcript v1
+//   @session router
+//   0 > show version\r\n
+//   152 < show version\r\nSW version: 1.4.0\r\nesr-200#
+//
+// Время - миллисекунды от начала сессии, ">" - отправлено устройству,
+// "<" - получено от устройства. Непечатные байты экранируются как \xNN.
+
+const TRANSCRIPT_HEADER: &str = "# rackit transcript v1";
+
+/// Направление передачи данных в записи сессии
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Sent,
+    Received,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptEvent {
+    pub elapsed: Duration,
+    pub direction: Direction,
+    pub data: Vec<u8>,
+}
+
+/// Запись одной сессии с устройством
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transcript {
+    pub session: String,
+    pub events: Vec<TranscriptEvent>,
+}
+
+impl Transcript {
+    /// Читает все сессии из файла записи
+    pub fn load_all<P: AsRef<Path>>(path: P) -> Result<Vec<Transcript>> {
+        let content = std::fs::read_to_string(path)?;
+        Self::parse_all(&content)
+    }
+
+    /// Читает сессию с указанным именем (или первую, если имя не задано)
+    pub fn load<P: AsRef<Path>>(path: P, session: Option<&str>) -> Result<Transcript> {
+        let path = path.as_ref();
+        let transcripts = Self::load_all(path)?;
+        let found = match session {
+            Some(name) => transcripts.into_iter().find(|t| t.session == name),
+            None => transcripts.into_iter().next(),
+        };
+        found.ok_or_else(|| Error::ConfigRead(format!(
+            "Сессия '{}' не найдена в {}", session.unwrap_or("*"), path.display()
+        )))
+    }
+
+    pub fn parse_all(content: &str) -> Result<Vec<Transcript>> {
+        let mut transcripts: Vec<Transcript> = Vec::new();
+
+        for (number, line) in content.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(session) = line.strip_prefix("@session ") {
+                transcripts.push(Transcript { session: session.to_string(), events: Vec::new() });
+                continue;
+            }
+
+            let parse_error = || Error::ConfigParse(format!("Строка записи сессии {}: '{}'", number + 1, line));
+            let mut parts = line.splitn(3, ' ');
+            let elapsed: u64 = parts.next().and_then(|t| t.parse().ok()).ok_or_else(parse_error)?;
+            let direction = match parts.next() {
+                Some(">") => Direction::Sent,
+                Some("<") => Direction::Received,
+                _ => return Err(parse_error()),
+            };
+            let data = unescape(parts.next().unwrap_or("")).ok_or_else(parse_error)?;
+
+            if transcripts.is_empty() {
+                transcripts.push(Transcript { session: String::new(), events: Vec::new() });
+            }
+            if let Some(transcript) = transcripts.last_mut() {
+                transcript.events.push(TranscriptEvent {
+                    elapsed: Duration::from_millis(elapsed),
+                    direction,
+                    data,
+                });
+            }
+        }
+
+        Ok(transcripts)
+    }
+}
+
+fn escape(data: &[u8]) -> String {
+    let mut escaped = String::with_capacity(data.len());
+    for &byte in data {
+        match byte {
+            b'\\' => escaped.push_str("\\\\"),
+            b'\r' => escaped.push_str("\\r"),
+            b'\n' => escaped.push_str("\\n"),
+            b'\t' => escaped.push_str("\\t"),
+            0x20..=0x7e => escaped.push(byte as char),
+            _ => escaped.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> Option<Vec<u8>> {
+    let bytes = text.as_bytes();
+    let mut data = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'\\' {
+            data.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1)? {
+            b'\\' => data.push(b'\\'),
+            b'r' => data.push(b'\r'),
+            b'n' => data.push(b'\n'),
+            b't' => data.push(b'\t'),
+            b'x' => {
+                let hex = text.get(i + 2..i + 4)?;
+                data.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 2;
+            }
+            _ => return None,
+        }
+        i += 2;
+    }
+    Some(data)
+}
+
+/// Обертка над любым транспортом, записывающая обмен данными в файл
+pub struct RecordingTransport<T: Transport> {
+    inner: T,
+    writer: BufWriter<File>,
+    started: Instant,
+    /// Возвращенные через `unread` данные, которые уже попали в запись
+    pending: VecDeque<u8>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    /// Дописывает сессию `session` в конец файла записи
+    pub fn new<P: AsRef<Path>>(inner: T, path: P, session: &str) -> Result<Self> {
+        let path = path.as_ref();
+        let is_new = !path.exists() || std::fs::metadata(path)?.len() == 0;
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        let mut writer = BufWriter::new(file);
+        if is_new {
+            writeln!(writer, "{}", TRANSCRIPT_HEADER)?;
+        }
+        writeln!(writer, "@session {}", session)?;
+        writer.flush()?;
+
+        Ok(Self { inner, writer, started: Instant::now(), pending: VecDeque::new() })
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    fn record(&mut self, direction: Direction, data: &[u8]) -> Result<()> {
+        let marker = match direction {
+            Direction::Sent => '>',
+            Direction::Received => '<',
+        };
+        writeln!(self.writer, "{} {} {}", self.started.elapsed().as_millis(), marker, escape(data))?;
+        // Сбрасываем сразу, чтобы запись сохранилась даже при аварийном завершении
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<T: Transport> Transport for RecordingTransport<T> {
+    fn connect(&mut self) -> Result<()> {
+        self.inner.connect()
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.inner.disconnect()
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        self.record(Direction::Sent, data)?;
+        self.inner.send(data)
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        if !self.pending.is_empty() {
+            return Ok(take_pending(&mut self.pending, buf));
+        }
+
+        let bytes_read = self.inner.read_chunk(buf, wait)?;
+        if bytes_read > 0 {
+            self.record(Direction::Received, &buf[..bytes_read])?;
+        }
+        Ok(bytes_read)
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.inner.set_timeout(timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.inner.timeout()
+    }
+}
+
+/// Воспроизводит записанную сессию: отдает полученные тогда данные
+/// и завершается ошибкой, если отправляется не то, что было записано
+pub struct ReplayTransport {
+    mock: MockTransport,
+    handle: MockHandle,
+}
+
+impl ReplayTransport {
+    pub fn new(transcript: &Transcript) -> Self {
+        Self::build(transcript, false)
+    }
+
+    /// Воспроизведение с исходными паузами между ответами устройства
+    pub fn with_timing(transcript: &Transcript) -> Self {
+        Self::build(transcript, true)
+    }
+
+    /// Загружает сессию из файла (первую, если имя не указано)
+    pub fn from_file<P: AsRef<Path>>(path: P, session: Option<&str>) -> Result<Self> {
+        Ok(Self::new(&Transcript::load(path, session)?))
+    }
+
+    fn build(transcript: &Transcript, timing: bool) -> Self {
+        let mut mock = MockTransport::new();
+        let mut last_elapsed = Duration::ZERO;
+
+        for event in &transcript.events {
+            if timing && event.direction == Direction::Received {
+                let gap = event.elapsed.saturating_sub(last_elapsed);
+                if !gap.is_zero() {
+                    mock = mock.delay(gap);
+                }
+            }
+            last_elapsed = event.elapsed;
+
+            mock = mock.step(match event.direction {
+                Direction::Sent => MockStep::Expect(event.data.clone()),
+                Direction::Received => MockStep::Respond(event.data.clone()),
+            });
+        }
+
+        let handle = mock.handle();
+        Self { mock, handle }
+    }
+
+    /// Проверяет, что сессия воспроизведена полностью и без расхождений
+    pub fn verify(&self) -> Result<()> {
+        self.handle.verify()
+    }
+
+    pub fn handle(&self) -> MockHandle {
+        self.handle.clone()
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn connect(&mut self) -> Result<()> {
+        self.mock.connect()
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        self.mock.disconnect()
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        self.mock.send(data)
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        self.mock.read_chunk(buf, wait)
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        self.mock.unread(data)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.mock.set_timeout(timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.mock.timeout()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_escape_roundtrip() {
+        let data = b"show\tversion\r\n\\ \x1b[0m\xff".to_vec();
+        let escaped = escape(&data);
+        assert!(!escaped.contains('\n'));
+        assert_eq!(unescape(&escaped).unwrap(), data);
+        assert!(unescape("bad\\q").is_none());
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let file = NamedTempFile::new().unwrap();
+
+        let mock = MockTransport::new()
+            .respond("esr-200# ")
+            .expect_command("show clock")
+            .respond("show clock\r\n12:00:00\r\nesr-200# ");
+        let mut recorder = RecordingTransport::new(mock, file.path(), "router").unwrap();
+        recorder.connect().unwrap();
+        recorder.receive_until_prompt("esr-200#").unwrap();
+        recorder.send_string("show clock").unwrap();
+        let original = recorder.receive_until_prompt("esr-200#").unwrap();
+        drop(recorder);
+
+        let transcripts = Transcript::load_all(file.path()).unwrap();
+        assert_eq!(transcripts.len(), 1);
+        assert_eq!(transcripts[0].session, "router");
+        assert_eq!(transcripts[0].events.len(), 3);
+        assert_eq!(transcripts[0].events[1].direction, Direction::Sent);
+
+        let mut replay = ReplayTransport::from_file(file.path(), Some("router")).unwrap();
+        replay.connect().unwrap();
+        assert_eq!(replay.receive_until_prompt("esr-200#").unwrap(), "esr-200# ");
+        replay.send_string("show clock").unwrap();
+        assert_eq!(replay.receive_until_prompt("esr-200#").unwrap(), original);
+        replay.verify().unwrap();
+
+        // Отклонение от записи - ошибка
+        let mut replay = ReplayTransport::from_file(file.path(), None).unwrap();
+        replay.connect().unwrap();
+        replay.receive_until_prompt("esr-200#").unwrap();
+        assert!(replay.send_string("show version").is_err());
+        assert!(replay.verify().is_err());
+    }
+}
@@ -1,31 +1,228 @@
-use crate::error::types::Result;
-use serialport::SerialPort;
+use crate::error::types::{Error, Result};
+use super::discovery::{available_ports, UsbSelector};
+use super::expect::{push_front, take_pending};
+use serialport::{DataBits, FlowControl, Parity, SerialPort, StopBits};
+use std::collections::VecDeque;
 use std::time::Duration;
 use std::io::{Read, Write};
 
+/// Формат кадра линии: биты данных, четность и стоповые биты (по умолчанию 8N1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialSettings {
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+}
+
+impl Default for SerialSettings {
+    fn default() -> Self {
+        Self {
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+        }
+    }
+}
+
 pub struct SerialTransport {
+    /// Путь к устройству или селектор USB адаптера (`usb:serial=...`)
     port_name: String,
     baud_rate: u32,
+    settings: SerialSettings,
+    flow_control: FlowControl,
+    dtr: Option<bool>,
+    rts: Option<bool>,
+    toggle_dtr_rts: Option<Duration>,
+    break_on_connect: Option<Duration>,
+    auto_baud_rates: Option<Vec<u32>>,
+    timeout: Duration,
     port: Option<Box<dyn SerialPort>>,
+    pending: VecDeque<u8>,
 }
 
 impl SerialTransport {
+    /// Порт в режиме 8N1 без управления потоком
     pub fn new(port_name: String, baud_rate: u32) -> Self {
         Self {
             port_name,
             baud_rate,
+            settings: SerialSettings::default(),
+            flow_control: FlowControl::None,
+            dtr: None,
+            rts: None,
+            toggle_dtr_rts: None,
+            break_on_connect: None,
+            auto_baud_rates: None,
+            timeout: Duration::from_secs(10),
             port: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Формат кадра целиком
+    pub fn with_serial_settings(mut self, settings: SerialSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    pub fn with_data_bits(mut self, data_bits: DataBits) -> Self {
+        self.settings.data_bits = data_bits;
+        self
+    }
+
+    pub fn with_parity(mut self, parity: Parity) -> Self {
+        self.settings.parity = parity;
+        self
+    }
+
+    pub fn with_stop_bits(mut self, stop_bits: StopBits) -> Self {
+        self.settings.stop_bits = stop_bits;
+        self
+    }
+
+    pub fn with_flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
+    /// Уровень DTR, выставляемый после открытия порта
+    pub fn with_dtr(mut self, level: bool) -> Self {
+        self.dtr = Some(level);
+        self
+    }
+
+    /// Уровень RTS, выставляемый после открытия порта
+    pub fn with_rts(mut self, level: bool) -> Self {
+        self.rts = Some(level);
+        self
+    }
+
+    /// После открытия порта сбросить DTR и RTS на `duration`,
+    /// затем выставить заданные уровни (по умолчанию - поднять)
+    pub fn with_dtr_rts_toggle(mut self, duration: Duration) -> Self {
+        self.toggle_dtr_rts = Some(duration);
+        self
+    }
+
+    /// Отправить break указанной длительности сразу после подключения
+    pub fn with_break_on_connect(mut self, duration: Duration) -> Self {
+        self.break_on_connect = Some(duration);
+        self
+    }
+
+    /// Определять скорость при подключении, перебирая `rates`
+    /// (см. `discovery::detect_baud_rate`)
+    pub fn with_auto_baud(mut self, rates: &[u32]) -> Self {
+        self.auto_baud_rates = Some(rates.to_vec());
+        self
+    }
+
+    /// Таймаут открытия порта и ожидания ответа
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Текущая скорость (после автоопределения - найденная)
+    pub fn baud_rate(&self) -> u32 {
+        self.baud_rate
+    }
+
+    /// Меняет скорость, в том числе у открытого порта
+    pub fn set_baud_rate(&mut self, baud_rate: u32) -> Result<()> {
+        if let Some(port) = self.port.as_mut() {
+            port.set_baud_rate(baud_rate)?;
+            port.clear(serialport::ClearBuffer::Input)?;
+        }
+        self.baud_rate = baud_rate;
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn port_mut(&mut self) -> Result<&mut Box<dyn SerialPort>> {
+        self.port.as_mut()
+            .ok_or_else(|| Error::Connection("Port not connected".to_string()))
+    }
+
+    pub fn set_dtr(&mut self, level: bool) -> Result<()> {
+        self.port_mut()?.write_data_terminal_ready(level)?;
+        Ok(())
+    }
+
+    pub fn set_rts(&mut self, level: bool) -> Result<()> {
+        self.port_mut()?.write_request_to_send(level)?;
+        Ok(())
+    }
+
+    /// Отправляет сигнал break (удержание линии в нуле) на `duration`;
+    /// используется для входа в загрузчик или ROMMON
+    pub fn send_break(&mut self, duration: Duration) -> Result<()> {
+        let port = self.port_mut()?;
+        port.set_break()?;
+        std::thread::sleep(duration);
+        port.clear_break()?;
+        Ok(())
+    }
+
+    /// Управляющие линии и break, настроенные для подключения
+    fn apply_line_control(&mut self) -> Result<()> {
+        if let Some(duration) = self.toggle_dtr_rts {
+            self.set_dtr(false)?;
+            self.set_rts(false)?;
+            std::thread::sleep(duration);
+            self.set_dtr(self.dtr.unwrap_or(true))?;
+            self.set_rts(self.rts.unwrap_or(true))?;
+        } else {
+            if let Some(level) = self.dtr {
+                self.set_dtr(level)?;
+            }
+            if let Some(level) = self.rts {
+                self.set_rts(level)?;
+            }
+        }
+
+        if let Some(duration) = self.break_on_connect {
+            self.send_break(duration)?;
         }
+        Ok(())
     }
 }
 
 impl super::traits::Transport for SerialTransport {
     fn connect(&mut self) -> Result<()> {
-        let port = serialport::new(&self.port_name, self.baud_rate)
-            .timeout(Duration::from_millis(3000))
+        // Селектор usb:... разрешается при каждом подключении: пути ttyUSB* меняются после перезагрузки
+        let path = if UsbSelector::is_selector(&self.port_name) {
+            let path = UsbSelector::parse(&self.port_name)?.resolve(&available_ports()?)?;
+            println!("Resolved {} to {}", self.port_name, path);
+            path
+        } else {
+            self.port_name.clone()
+        };
+
+        let port = serialport::new(&path, self.baud_rate)
+            .data_bits(self.settings.data_bits)
+            .parity(self.settings.parity)
+            .stop_bits(self.settings.stop_bits)
+            .flow_control(self.flow_control)
+            .timeout(self.timeout)
             .open()?;
         
         self.port = Some(port);
+        self.pending.clear();
+        if let Err(e) = self.apply_line_control() {
+            self.port = None;
+            return Err(e);
+        }
+
+        if let Some(rates) = self.auto_baud_rates.clone() {
+            match super::discovery::detect_baud_rate(self, &rates, super::discovery::AUTO_BAUD_PROBE) {
+                Ok(baud_rate) => println!("Detected baud rate {} on {}", baud_rate, self.port_name),
+                Err(e) => {
+                    self.port = None;
+                    return Err(e);
+                }
+            }
+        }
         println!("Connected to {}", self.port_name);
         Ok(())
     }
@@ -45,88 +242,44 @@ impl super::traits::Transport for SerialTransport {
                 port.flush()?;
                 Ok(())
             }
-            None => Err(crate::error::types::Error::Connection("Port not connected".to_string()))
+            None => Err(Error::Connection("Port not connected".to_string()))
         }
     }
 
-    fn receive_until(&mut self, delimiter: u8) -> Result<Vec<u8>> {
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        if !self.pending.is_empty() {
+            return Ok(take_pending(&mut self.pending, buf));
+        }
+
         match &mut self.port {
             Some(port) => {
-                let mut buffer = Vec::new();
-                let mut byte = [0u8; 1];
-                
-                loop {
-                    match port.read_exact(&mut byte) {
-                        Ok(_) => {
-                            buffer.push(byte[0]);
-                            if byte[0] == delimiter {
-                                break;
-                            }
-                        }
-                        Err(e) => return Err(e.into()),
-                    }
+                port.set_timeout(wait.max(Duration::from_millis(1)))?;
+                match port.read(buf) {
+                    Ok(bytes_read) => Ok(bytes_read),
+                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => Ok(0),
+                    Err(e) => Err(e.into()),
                 }
-                Ok(buffer)
             }
-            None => Err(crate::error::types::Error::Connection("Port not connected".to_string()))
+            None => Err(Error::Connection("Port not connected".to_string()))
         }
     }
 
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
     fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = timeout;
         match &mut self.port {
             Some(port) => {
                 port.set_timeout(timeout)?;
                 Ok(())
             }
-            None => Err(crate::error::types::Error::Connection("Port not connected".to_string()))
+            None => Err(Error::Connection("Port not connected".to_string()))
         }
     }
 
-    fn receive_until_prompt(&mut self, prompt: &str) -> Result<String> {
-        match &mut self.port {
-            Some(port) => {
-                let mut buffer = Vec::new();
-                let mut temp_buffer = [0u8; 1024];
-                let start_time = std::time::Instant::now();
-                let timeout_duration = Duration::from_secs(10); // 10 секунд таймаут
-                
-                loop {
-                    // Проверяем таймаут
-                    if start_time.elapsed() > timeout_duration {
-                        break;
-                    }
-                    
-                    match port.read(&mut temp_buffer) {
-                        Ok(bytes_read) if bytes_read > 0 => {
-                            buffer.extend_from_slice(&temp_buffer[..bytes_read]);
-                            
-                            // Преобразуем в строку для проверки
-                            let text = String::from_utf8_lossy(&buffer);
-                            println!("DEBUG: Читаем данные ({} байт): '{}'", buffer.len(), text);
-                            
-                            // Если нашли промпт - возвращаем результат
-                            if text.contains(prompt) {
-                                return Ok(text.to_string());
-                            }
-                        }
-                        Ok(_) => {
-                            // Нет данных, ждем еще
-                            std::thread::sleep(Duration::from_millis(50));
-                        }
-                        Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                            // Таймаут чтения - продолжаем ждать
-                            std::thread::sleep(Duration::from_millis(50));
-                        }
-                        Err(e) => return Err(e.into()),
-                    }
-                }
-                
-                // Если вышли по таймауту - возвращаем что накопили
-                Ok(String::from_utf8_lossy(&buffer).to_string())
-            }
-            None => Err(crate::error::types::Error::Connection("Port not connected".to_string()))
-        }
+    fn timeout(&self) -> Duration {
+        self.timeout
     }
 }
-
-
@@ -0,0 +1,327 @@
+use crate::error::types::{Error, Result};
+use super::expect::{push_front, take_pending};
+use ssh2::{Channel, CheckResult, KnownHostFileKind, Session};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub struct SshTransport {
+    host: String,
+    port: u16,
+    username: String,
+    password: Option<String>,
+    key_path: Option<PathBuf>,
+    known_hosts_path: Option<PathBuf>,
+    timeout: Duration,
+    session: Option<Session>,
+    channel: Option<Channel>,
+    pending: VecDeque<u8>,
+}
+
+impl SshTransport {
+    pub fn new(host: String, port: u16, username: String) -> Self {
+        Self {
+            host,
+            port,
+            username,
+            password: None,
+            key_path: None,
+            known_hosts_path: None,
+            timeout: Duration::from_secs(10),
+            session: None,
+            channel: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Аутентификация по паролю
+    pub fn with_password(mut self, password: &str) -> Self {
+        self.password = Some(password.to_string());
+        self
+    }
+
+    /// Аутентификация по приватному ключу (пробуется раньше пароля)
+    pub fn with_key_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.key_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Файл known_hosts для проверки ключа сервера (по умолчанию ~/.ssh/known_hosts)
+    pub fn with_known_hosts<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.known_hosts_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Таймаут подключения и операций чтения
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn known_hosts_file(&self) -> Result<PathBuf> {
+        match &self.known_hosts_path {
+            Some(path) => Ok(path.clone()),
+            None => std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".ssh").join("known_hosts"))
+                .ok_or_else(|| Error::Connection(
+                    "Не удалось определить путь к known_hosts: переменная HOME не задана".to_string()
+                )),
+        }
+    }
+
+    /// Сверяет ключ сервера с known_hosts; неизвестный или измененный ключ - ошибка
+    fn verify_host_key(&self, session: &Session) -> Result<()> {
+        let (key, _) = session.host_key()
+            .ok_or_else(|| Error::Connection("Сервер не предоставил ключ хоста".to_string()))?;
+        self.check_host_key(session, key)
+    }
+
+    /// Ищет ключ `key` (в формате протокола SSH) для хоста и порта в known_hosts
+    fn check_host_key(&self, session: &Session, key: &[u8]) -> Result<()> {
+        let path = self.known_hosts_file()?;
+        let mut known_hosts = session.known_hosts()?;
+        if path.exists() {
+            known_hosts.read_file(&path, KnownHostFileKind::OpenSSH)?;
+        }
+
+        match known_hosts.check_port(&self.host, self.port, key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::NotFound => Err(Error::Connection(format!(
+                "Ключ хоста {}:{} отсутствует в {}", self.host, self.port, path.display()
+            ))),
+            CheckResult::Mismatch => Err(Error::Connection(format!(
+                "Ключ хоста {}:{} не совпадает с записью в {}", self.host, self.port, path.display()
+            ))),
+            CheckResult::Failure => Err(Error::Connection(format!(
+                "Не удалось проверить ключ хоста {}:{}", self.host, self.port
+            ))),
+        }
+    }
+
+    fn authenticate(&self, session: &Session) -> Result<()> {
+        if let Some(key_path) = &self.key_path {
+            if let Err(e) = session.userauth_pubkey_file(&self.username, None, key_path, None) {
+                if self.password.is_none() {
+                    return Err(Error::Authentication(format!("SSH: ключ пользователя '{}' отклонен: {}", self.username, e)));
+                }
+            }
+        }
+
+        if !session.authenticated() {
+            if let Some(password) = &self.password {
+                session.userauth_password(&self.username, password).map_err(|e| Error::Authentication(
+                    format!("SSH: пароль пользователя '{}' отклонен: {}", self.username, e)
+                ))?;
+            }
+        }
+
+        if session.authenticated() {
+            Ok(())
+        } else {
+            Err(Error::Authentication(format!("SSH: аутентификация пользователя '{}' не удалась", self.username)))
+        }
+    }
+}
+
+impl super::traits::Transport for SshTransport {
+    fn connect(&mut self) -> Result<()> {
+        let addr = (self.host.as_str(), self.port).to_socket_addrs()
+            .map_err(|e| Error::Connection(format!("{}:{}: {}", self.host, self.port, e)))?
+            .next()
+            .ok_or_else(|| Error::Connection(format!("{}:{}: адрес не найден", self.host, self.port)))?;
+
+        let tcp = TcpStream::connect_timeout(&addr, self.timeout)
+            .map_err(|e| Error::Connection(format!("{}:{}: {}", self.host, self.port, e)))?;
+
+        let mut session = Session::new()?;
+        session.set_tcp_stream(tcp);
+        session.set_timeout(self.timeout.as_millis() as u32);
+        session.handshake()?;
+
+        self.verify_host_key(&session)?;
+        self.authenticate(&session)?;
+
+        let mut channel = session.channel_session()?;
+        channel.request_pty("vt100", None, Some((80, 24, 0, 0)))?;
+        channel.shell()?;
+
+        self.session = Some(session);
+        self.channel = Some(channel);
+        self.pending.clear();
+        println!("Connected to {}:{}", self.host, self.port);
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        if let Some(mut channel) = self.channel.take() {
+            let _ = channel.close();
+        }
+        if let Some(session) = self.session.take() {
+            let _ = session.disconnect(None, "rackit disconnect", None);
+            println!("Disconnected from {}:{}", self.host, self.port);
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        match &mut self.channel {
+            Some(channel) => {
+                channel.write_all(data)?;
+                channel.flush()?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        if !self.pending.is_empty() {
+            return Ok(take_pending(&mut self.pending, buf));
+        }
+
+        let (session, channel) = match (&self.session, &mut self.channel) {
+            (Some(session), Some(channel)) => (session, channel),
+            _ => return Err(Error::Connection("Port not connected".to_string())),
+        };
+
+        // Таймаут libssh2 задается в миллисекундах, 0 означает "без ограничения"
+        session.set_timeout(wait.as_millis().clamp(1, u32::MAX as u128) as u32);
+        let result = channel.read(buf);
+        session.set_timeout(self.timeout.as_millis() as u32);
+
+        match result {
+            Ok(0) if channel.eof() => {
+                Err(Error::Connection(format!("Connection closed by {}:{}", self.host, self.port)))
+            }
+            Ok(bytes_read) => Ok(bytes_read),
+            Err(ref e) if matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            ) => Ok(0),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = timeout;
+        match &self.session {
+            Some(session) => {
+                session.set_timeout(timeout.as_millis() as u32);
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Transport;
+
+    #[test]
+    fn test_known_hosts_path() {
+        let transport = SshTransport::new("127.0.0.1".to_string(), 22, "admin".to_string())
+            .with_known_hosts("/tmp/rackit_known_hosts");
+        assert_eq!(transport.known_hosts_file().unwrap(), PathBuf::from("/tmp/rackit_known_hosts"));
+    }
+
+    /// Ключ ssh-rsa в формате протокола SSH; `seed` заполняет модуль
+    fn rsa_key(seed: u8) -> Vec<u8> {
+        let mut key = Vec::new();
+        for field in [&b"ssh-rsa"[..], &[1, 0, 1], &[seed; 64]] {
+            key.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            key.extend_from_slice(field);
+        }
+        key
+    }
+
+    #[test]
+    fn test_host_key_checked_against_known_hosts() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        let session = Session::new().unwrap();
+        let mut known_hosts = session.known_hosts().unwrap();
+        known_hosts.add("[127.0.0.1]:2222", &rsa_key(1), "rackit", ssh2::KnownHostKeyFormat::SshRsa).unwrap();
+        known_hosts.write_file(&path, KnownHostFileKind::OpenSSH).unwrap();
+
+        let transport = |port: u16| SshTransport::new("127.0.0.1".to_string(), port, "admin".to_string())
+            .with_known_hosts(&path);
+        transport(2222).check_host_key(&session, &rsa_key(1)).unwrap();
+
+        match transport(2222).check_host_key(&session, &rsa_key(2)) {
+            Err(Error::Connection(message)) => assert!(message.contains("не совпадает"), "{}", message),
+            other => panic!("ожидался отказ из-за другого ключа, получено {:?}", other),
+        }
+        match transport(2223).check_host_key(&session, &rsa_key(1)) {
+            Err(Error::Connection(message)) => assert!(message.contains("отсутствует"), "{}", message),
+            other => panic!("ожидался отказ для неизвестного хоста, получено {:?}", other),
+        }
+
+        let missing = SshTransport::new("127.0.0.1".to_string(), 2222, "admin".to_string())
+            .with_known_hosts(dir.path().join("missing"));
+        assert!(matches!(missing.check_host_key(&session, &rsa_key(1)), Err(Error::Connection(_))));
+    }
+
+    #[test]
+    fn test_authentication_failure_mapped() {
+        // Без ключа и пароля сессия не аутентифицирована: ошибка входа, а не подключения
+        let session = Session::new().unwrap();
+        let transport = SshTransport::new("127.0.0.1".to_string(), 22, "admin".to_string());
+        match transport.authenticate(&session) {
+            Err(Error::Authentication(message)) => assert!(message.contains("admin")),
+            other => panic!("ожидалась ошибка аутентификации, получено {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_connect_fails_without_ssh_server() {
+        // Сервер, который сразу закрывает соединение: рукопожатие не должно пройти
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (socket, _) = listener.accept().unwrap();
+            drop(socket);
+        });
+
+        let mut transport = SshTransport::new("127.0.0.1".to_string(), port, "admin".to_string())
+            .with_password("secret")
+            .with_timeout(Duration::from_secs(2));
+        assert!(transport.connect().is_err());
+        server.join().unwrap();
+    }
+
+    /// Проверка против реального sshd:
+    /// RACKIT_SSH_HOST, RACKIT_SSH_PORT, RACKIT_SSH_USER, RACKIT_SSH_PASSWORD или RACKIT_SSH_KEY
+    #[test]
+    #[ignore]
+    fn test_local_sshd() {
+        let host = std::env::var("RACKIT_SSH_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let port = std::env::var("RACKIT_SSH_PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(22);
+        let user = std::env::var("RACKIT_SSH_USER").expect("RACKIT_SSH_USER");
+
+        let mut transport = SshTransport::new(host, port, user);
+        if let Ok(password) = std::env::var("RACKIT_SSH_PASSWORD") {
+            transport = transport.with_password(&password);
+        }
+        if let Ok(key) = std::env::var("RACKIT_SSH_KEY") {
+            transport = transport.with_key_file(key);
+        }
+
+        transport.connect().unwrap();
+        transport.send_string("echo rackit-$((40+2))").unwrap();
+        let output = transport.receive_until_prompt("rackit-42").unwrap();
+        assert!(output.contains("rackit-42"));
+        transport.disconnect().unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+use crate::error::types::{Error, Result};
+use super::expect::{push_front, take_pending};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Открывает TCP соединение, перебирая все адреса хоста
+pub(crate) fn connect_tcp(host: &str, port: u16, timeout: Duration) -> Result<TcpStream> {
+    let addrs = (host, port).to_socket_addrs()
+        .map_err(|e| Error::Connection(format!("{}:{}: {}", host, port, e)))?;
+
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_nodelay(true)?;
+                return Ok(stream);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(match last_error {
+        Some(e) => Error::Connection(format!("{}:{}: {}", host, port, e)),
+        None => Error::Connection(format!("{}:{}: адрес не найден", host, port)),
+    })
+}
+
+/// Читает из сокета, ожидая данные не дольше `wait`.
+/// `Ok(None)` - соединение закрыто удаленной стороной
+pub(crate) fn read_stream(stream: &mut TcpStream, buf: &mut [u8], wait: Duration) -> Result<Option<usize>> {
+    // Нулевой таймаут для сокета недопустим
+    stream.set_read_timeout(Some(wait.max(Duration::from_millis(1))))?;
+    match stream.read(buf) {
+        Ok(0) => Ok(None),
+        Ok(bytes_read) => Ok(Some(bytes_read)),
+        Err(ref e) if matches!(
+            e.kind(),
+            std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+        ) => Ok(Some(0)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Прямое TCP подключение к порту консольного сервера (ser2net в режиме raw и т.п.)
+pub struct TcpTransport {
+    host: String,
+    port: u16,
+    timeout: Duration,
+    stream: Option<TcpStream>,
+    pending: VecDeque<u8>,
+}
+
+impl TcpTransport {
+    pub fn new(host: String, port: u16) -> Self {
+        Self {
+            host,
+            port,
+            timeout: Duration::from_secs(10),
+            stream: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Таймаут подключения и ожидания ответа
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl super::traits::Transport for TcpTransport {
+    fn connect(&mut self) -> Result<()> {
+        self.stream = Some(connect_tcp(&self.host, self.port, self.timeout)?);
+        self.pending.clear();
+        println!("Connected to {}:{}", self.host, self.port);
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        if let Some(stream) = self.stream.take() {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+            println!("Disconnected from {}:{}", self.host, self.port);
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        match &mut self.stream {
+            Some(stream) => {
+                stream.write_all(data)?;
+                stream.flush()?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        if !self.pending.is_empty() {
+            return Ok(take_pending(&mut self.pending, buf));
+        }
+
+        match &mut self.stream {
+            Some(stream) => read_stream(stream, buf, wait)?
+                .ok_or_else(|| Error::Connection(format!("Connection closed by {}:{}", self.host, self.port))),
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = timeout;
+        match &mut self.stream {
+            Some(stream) => {
+                stream.set_read_timeout(Some(timeout))?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Transport;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn test_raw_tcp_console() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut line = Vec::new();
+            let mut byte = [0u8; 1];
+            while !line.ends_with(b"\r\n") {
+                socket.read_exact(&mut byte).unwrap();
+                line.push(byte[0]);
+            }
+            // Байт 0xFF в raw режиме передается как есть
+            socket.write_all(b"\xffesr-200# ").unwrap();
+            line
+        });
+
+        let mut transport = TcpTransport::new("127.0.0.1".to_string(), port);
+        transport.connect().unwrap();
+        transport.send_string("").unwrap();
+
+        let prompt = transport.receive_until_prompt("esr-200#").unwrap();
+        assert!(prompt.ends_with("esr-200# "));
+        assert_eq!(server.join().unwrap(), b"\r\n");
+    }
+}
@@ -0,0 +1,652 @@
+use crate::error::types::{Error, Result};
+use super::expect::{push_front, take_pending};
+use super::serial::SerialSettings;
+use super::tcp::{connect_tcp, read_stream};
+use serialport::{DataBits, Parity, StopBits};
+use std::collections::VecDeque;
+use std::io::Write;
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+// Команды протокола Telnet (RFC 854)
+pub(crate) const IAC: u8 = 255;
+pub(crate) const DONT: u8 = 254;
+pub(crate) const DO: u8 = 253;
+pub(crate) const WONT: u8 = 252;
+pub(crate) const WILL: u8 = 251;
+pub(crate) const SB: u8 = 250;
+pub(crate) const SE: u8 = 240;
+
+// Опции Telnet
+pub(crate) const OPT_ECHO: u8 = 1;
+pub(crate) const OPT_SGA: u8 = 3;
+pub(crate) const OPT_TTYPE: u8 = 24;
+pub(crate) const OPT_NAWS: u8 = 31;
+pub(crate) const OPT_COM_PORT: u8 = 44;
+
+// Подкоманды TTYPE (RFC 1091)
+const TTYPE_IS: u8 = 0;
+const TTYPE_SEND: u8 = 1;
+
+// Подкоманды COM-PORT-OPTION (RFC 2217); ответ сервера = команда + 100
+const COM_PORT_SET_BAUDRATE: u8 = 1;
+const COM_PORT_SET_DATASIZE: u8 = 2;
+const COM_PORT_SET_PARITY: u8 = 3;
+const COM_PORT_SET_STOPSIZE: u8 = 4;
+const COM_PORT_SERVER_OFFSET: u8 = 100;
+
+/// Состояние согласования COM-PORT-OPTION
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ComPortState {
+    /// Опция не запрашивалась
+    Disabled,
+    /// Отправлен WILL, ждем ответа сервера
+    Requested,
+    /// Сервер отказал (DONT)
+    Refused,
+    /// Сервер подтвердил установку скорости
+    Acknowledged(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ParserState {
+    Data,
+    Iac,
+    Negotiate(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+/// Разбор потока Telnet: отделяет данные от команд и формирует ответы на согласование опций
+pub(crate) struct TelnetParser {
+    state: ParserState,
+    subnegotiation: Vec<u8>,
+    local_enabled: [bool; 256],
+    remote_enabled: [bool; 256],
+    terminal_type: String,
+    window_size: (u16, u16),
+    com_port_baud_rate: Option<u32>,
+    com_port_settings: SerialSettings,
+    com_port_state: ComPortState,
+}
+
+impl TelnetParser {
+    pub(crate) fn new(terminal_type: String, window_size: (u16, u16)) -> Self {
+        Self {
+            state: ParserState::Data,
+            subnegotiation: Vec::new(),
+            local_enabled: [false; 256],
+            remote_enabled: [false; 256],
+            terminal_type,
+            window_size,
+            com_port_baud_rate: None,
+            com_port_settings: SerialSettings::default(),
+            com_port_state: ComPortState::Disabled,
+        }
+    }
+
+    /// Запрашивает COM-PORT-OPTION: возвращает байты WILL для отправки серверу.
+    /// Параметры порта (скорость и формат кадра) передаются после ответа DO
+    pub(crate) fn request_com_port(&mut self, baud_rate: u32, settings: SerialSettings) -> Vec<u8> {
+        self.com_port_baud_rate = Some(baud_rate);
+        self.com_port_settings = settings;
+        self.com_port_state = ComPortState::Requested;
+        self.local_enabled[OPT_COM_PORT as usize] = true;
+        vec![IAC, WILL, OPT_COM_PORT]
+    }
+
+    pub(crate) fn com_port_state(&self) -> ComPortState {
+        self.com_port_state
+    }
+
+    /// Опции, которые мы готовы включить на своей стороне
+    fn local_supported(option: u8) -> bool {
+        matches!(option, OPT_TTYPE | OPT_NAWS | OPT_SGA | OPT_COM_PORT)
+    }
+
+    /// Опции, которые мы разрешаем включить удаленной стороне
+    fn remote_supported(option: u8) -> bool {
+        matches!(option, OPT_ECHO | OPT_SGA)
+    }
+
+    /// Разбирает входящие байты: полезные данные попадают в `data`, ответы сервера - в `reply`
+    pub(crate) fn feed(&mut self, input: &[u8], data: &mut Vec<u8>, reply: &mut Vec<u8>) {
+        for &byte in input {
+            self.state = match self.state {
+                ParserState::Data => {
+                    if byte == IAC {
+                        ParserState::Iac
+                    } else {
+                        data.push(byte);
+                        ParserState::Data
+                    }
+                }
+                ParserState::Iac => match byte {
+                    IAC => {
+                        data.push(IAC);
+                        ParserState::Data
+                    }
+                    DO | DONT | WILL | WONT => ParserState::Negotiate(byte),
+                    SB => {
+                        self.subnegotiation.clear();
+                        ParserState::Subnegotiation
+                    }
+                    // NOP, GA и прочие однобайтовые команды игнорируем
+                    _ => ParserState::Data,
+                },
+                ParserState::Negotiate(command) => {
+                    self.negotiate(command, byte, reply);
+                    ParserState::Data
+                }
+                ParserState::Subnegotiation => {
+                    if byte == IAC {
+                        ParserState::SubnegotiationIac
+                    } else {
+                        self.subnegotiation.push(byte);
+                        ParserState::Subnegotiation
+                    }
+                }
+                ParserState::SubnegotiationIac => match byte {
+                    SE => {
+                        let payload = std::mem::take(&mut self.subnegotiation);
+                        self.handle_subnegotiation(&payload, reply);
+                        ParserState::Data
+                    }
+                    IAC => {
+                        self.subnegotiation.push(IAC);
+                        ParserState::Subnegotiation
+                    }
+                    _ => ParserState::Subnegotiation,
+                },
+            };
+        }
+    }
+
+    /// Отвечает на DO/DONT/WILL/WONT только при изменении состояния опции,
+    /// чтобы не зациклить согласование (RFC 1143)
+    fn negotiate(&mut self, command: u8, option: u8, reply: &mut Vec<u8>) {
+        let idx = option as usize;
+        match command {
+            DO => {
+                if option == OPT_COM_PORT && self.com_port_baud_rate.is_none() {
+                    reply.extend_from_slice(&[IAC, WONT, option]);
+                } else if Self::local_supported(option) {
+                    if !self.local_enabled[idx] {
+                        self.local_enabled[idx] = true;
+                        reply.extend_from_slice(&[IAC, WILL, option]);
+                    }
+                    if option == OPT_NAWS {
+                        self.write_window_size(reply);
+                    }
+                    if option == OPT_COM_PORT {
+                        self.write_com_port_settings(reply);
+                    }
+                } else {
+                    reply.extend_from_slice(&[IAC, WONT, option]);
+                }
+            }
+            DONT if self.local_enabled[idx] => {
+                self.local_enabled[idx] = false;
+                if option == OPT_COM_PORT {
+                    self.com_port_state = ComPortState::Refused;
+                }
+                reply.extend_from_slice(&[IAC, WONT, option]);
+            }
+            WILL => {
+                if Self::remote_supported(option) {
+                    if !self.remote_enabled[idx] {
+                        self.remote_enabled[idx] = true;
+                        reply.extend_from_slice(&[IAC, DO, option]);
+                    }
+                } else {
+                    reply.extend_from_slice(&[IAC, DONT, option]);
+                }
+            }
+            WONT if self.remote_enabled[idx] => {
+                self.remote_enabled[idx] = false;
+                reply.extend_from_slice(&[IAC, DONT, option]);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_subnegotiation(&mut self, payload: &[u8], reply: &mut Vec<u8>) {
+        match payload {
+            [OPT_TTYPE, TTYPE_SEND, ..] => {
+                reply.extend_from_slice(&[IAC, SB, OPT_TTYPE, TTYPE_IS]);
+                reply.extend_from_slice(self.terminal_type.as_bytes());
+                reply.extend_from_slice(&[IAC, SE]);
+            }
+            [OPT_COM_PORT, command, a, b, c, d]
+                if *command == COM_PORT_SET_BAUDRATE + COM_PORT_SERVER_OFFSET =>
+            {
+                self.com_port_state = ComPortState::Acknowledged(u32::from_be_bytes([*a, *b, *c, *d]));
+            }
+            _ => {}
+        }
+    }
+
+    fn write_com_port_settings(&self, reply: &mut Vec<u8>) {
+        let Some(baud_rate) = self.com_port_baud_rate else {
+            return;
+        };
+
+        let mut write_command = |command: u8, value: &[u8]| {
+            reply.extend_from_slice(&[IAC, SB, OPT_COM_PORT, command]);
+            reply.extend_from_slice(&escape_iac(value));
+            reply.extend_from_slice(&[IAC, SE]);
+        };
+
+        // Значения RFC 2217: четность NONE=1, ODD=2, EVEN=3; стоп-биты 1=1, 2=2
+        let settings = self.com_port_settings;
+        let data_size = match settings.data_bits {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8,
+        };
+        let parity = match settings.parity {
+            Parity::None => 1,
+            Parity::Odd => 2,
+            Parity::Even => 3,
+        };
+        let stop_size = match settings.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+
+        write_command(COM_PORT_SET_BAUDRATE, &baud_rate.to_be_bytes());
+        write_command(COM_PORT_SET_DATASIZE, &[data_size]);
+        write_command(COM_PORT_SET_PARITY, &[parity]);
+        write_command(COM_PORT_SET_STOPSIZE, &[stop_size]);
+    }
+
+    fn write_window_size(&self, reply: &mut Vec<u8>) {
+        let (width, height) = self.window_size;
+        reply.extend_from_slice(&[IAC, SB, OPT_NAWS]);
+        for byte in width.to_be_bytes().into_iter().chain(height.to_be_bytes()) {
+            // 255 внутри подсогласования удваивается
+            reply.push(byte);
+            if byte == IAC {
+                reply.push(IAC);
+            }
+        }
+        reply.extend_from_slice(&[IAC, SE]);
+    }
+}
+
+/// Экранирует байт IAC в исходящих данных
+pub(crate) fn escape_iac(data: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+    for &byte in data {
+        escaped.push(byte);
+        if byte == IAC {
+            escaped.push(IAC);
+        }
+    }
+    escaped
+}
+
+pub struct TelnetTransport {
+    host: String,
+    port: u16,
+    timeout: Duration,
+    terminal_type: String,
+    window_size: (u16, u16),
+    com_port_baud_rate: Option<u32>,
+    com_port_settings: SerialSettings,
+    stream: Option<TcpStream>,
+    parser: TelnetParser,
+    pending: VecDeque<u8>,
+}
+
+impl TelnetTransport {
+    pub fn new(host: String, port: u16) -> Self {
+        let terminal_type = "VT100".to_string();
+        let window_size = (80, 24);
+        Self {
+            host,
+            port,
+            timeout: Duration::from_secs(10),
+            parser: TelnetParser::new(terminal_type.clone(), window_size),
+            terminal_type,
+            window_size,
+            com_port_baud_rate: None,
+            com_port_settings: SerialSettings::default(),
+            stream: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Таймаут подключения, согласования опций и ожидания ответа
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Тип терминала, сообщаемый серверу через TTYPE
+    pub fn with_terminal_type(mut self, terminal_type: &str) -> Self {
+        self.terminal_type = terminal_type.to_string();
+        self
+    }
+
+    /// Размер окна (ширина, высота), сообщаемый серверу через NAWS
+    pub fn with_window_size(mut self, width: u16, height: u16) -> Self {
+        self.window_size = (width, height);
+        self
+    }
+
+    /// Включает управление удаленным последовательным портом по RFC 2217
+    /// (консольные серверы ser2net, Opengear и т.п.): при подключении
+    /// серверу передаются скорость и формат кадра (`with_com_port_settings`, по умолчанию 8N1)
+    pub fn with_com_port_baud_rate(mut self, baud_rate: u32) -> Self {
+        self.com_port_baud_rate = Some(baud_rate);
+        self
+    }
+
+    /// Биты данных, четность и стоповые биты удаленного порта RFC 2217
+    pub fn with_com_port_settings(mut self, settings: SerialSettings) -> Self {
+        self.com_port_settings = settings;
+        self
+    }
+
+    /// Согласует COM-PORT-OPTION и ждет подтверждения скорости от сервера
+    fn negotiate_com_port(&mut self, baud_rate: u32) -> Result<()> {
+        let request = self.parser.request_com_port(baud_rate, self.com_port_settings);
+        if let Some(stream) = self.stream.as_mut() {
+            stream.write_all(&request)?;
+            stream.flush()?;
+        }
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            match self.parser.com_port_state() {
+                ComPortState::Acknowledged(_) => return Ok(()),
+                ComPortState::Refused => {
+                    return Err(Error::Connection(format!(
+                        "{}:{}: сервер не поддерживает RFC 2217 (COM-PORT-OPTION)", self.host, self.port
+                    )));
+                }
+                _ => {}
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::Connection(format!(
+                    "{}:{}: сервер не подтвердил скорость {} бод", self.host, self.port, baud_rate
+                )));
+            }
+
+            self.fill_pending(deadline - now)?;
+        }
+    }
+
+    /// Читает очередную порцию данных из сокета (ожидая не дольше `wait`),
+    /// попутно отвечая на согласование опций.
+    /// Возвращает количество полезных байт, добавленных в буфер
+    fn fill_pending(&mut self, wait: Duration) -> Result<usize> {
+        let stream = self.stream.as_mut()
+            .ok_or_else(|| Error::Connection("Port not connected".to_string()))?;
+
+        let mut raw = [0u8; 1024];
+        let bytes_read = read_stream(stream, &mut raw, wait)?
+            .ok_or_else(|| Error::Connection(format!("Connection closed by {}:{}", self.host, self.port)))?;
+        if bytes_read == 0 {
+            return Ok(0);
+        }
+
+        let mut data = Vec::new();
+        let mut reply = Vec::new();
+        self.parser.feed(&raw[..bytes_read], &mut data, &mut reply);
+
+        if !reply.is_empty() {
+            stream.write_all(&reply)?;
+            stream.flush()?;
+        }
+
+        let count = data.len();
+        self.pending.extend(data);
+        Ok(count)
+    }
+}
+
+impl super::traits::Transport for TelnetTransport {
+    fn connect(&mut self) -> Result<()> {
+        self.stream = Some(connect_tcp(&self.host, self.port, self.timeout)?);
+        self.parser = TelnetParser::new(self.terminal_type.clone(), self.window_size);
+        self.pending.clear();
+
+        if let Some(baud_rate) = self.com_port_baud_rate {
+            if let Err(e) = self.negotiate_com_port(baud_rate) {
+                self.stream = None;
+                return Err(e);
+            }
+        }
+
+        println!("Connected to {}:{}", self.host, self.port);
+        Ok(())
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        if let Some(stream) = self.stream.take() {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+            println!("Disconnected from {}:{}", self.host, self.port);
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        match &mut self.stream {
+            Some(stream) => {
+                stream.write_all(&escape_iac(data))?;
+                stream.flush()?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        if self.pending.is_empty() {
+            self.fill_pending(wait)?;
+        }
+        Ok(take_pending(&mut self.pending, buf))
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        push_front(&mut self.pending, data);
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        self.timeout = timeout;
+        match &mut self.stream {
+            Some(stream) => {
+                stream.set_read_timeout(Some(timeout))?;
+                Ok(())
+            }
+            None => Err(Error::Connection("Port not connected".to_string()))
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::Transport;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn feed(parser: &mut TelnetParser, input: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let mut data = Vec::new();
+        let mut reply = Vec::new();
+        parser.feed(input, &mut data, &mut reply);
+        (data, reply)
+    }
+
+    #[test]
+    fn test_parser_negotiation() {
+        let mut parser = TelnetParser::new("VT100".to_string(), (132, 48));
+
+        let (data, reply) = feed(&mut parser, &[IAC, WILL, OPT_ECHO, b'o', b'k', IAC, WILL, OPT_SGA]);
+        assert_eq!(data, b"ok");
+        assert_eq!(reply, vec![IAC, DO, OPT_ECHO, IAC, DO, OPT_SGA]);
+
+        // Повторное предложение не должно порождать ответ
+        let (_, reply) = feed(&mut parser, &[IAC, WILL, OPT_ECHO]);
+        assert!(reply.is_empty());
+
+        let (_, reply) = feed(&mut parser, &[IAC, DO, OPT_NAWS]);
+        assert_eq!(reply, vec![IAC, WILL, OPT_NAWS, IAC, SB, OPT_NAWS, 0, 132, 0, 48, IAC, SE]);
+
+        let (_, reply) = feed(&mut parser, &[IAC, DO, OPT_TTYPE, IAC, SB, OPT_TTYPE, TTYPE_SEND, IAC, SE]);
+        let mut expected = vec![IAC, WILL, OPT_TTYPE, IAC, SB, OPT_TTYPE, TTYPE_IS];
+        expected.extend_from_slice(b"VT100");
+        expected.extend_from_slice(&[IAC, SE]);
+        assert_eq!(reply, expected);
+
+        // Неизвестные опции отклоняются
+        let (_, reply) = feed(&mut parser, &[IAC, DO, 99, IAC, WILL, 98]);
+        assert_eq!(reply, vec![IAC, WONT, 99, IAC, DONT, 98]);
+    }
+
+    #[test]
+    fn test_parser_split_and_escaped_iac() {
+        let mut parser = TelnetParser::new("VT100".to_string(), (80, 24));
+
+        let (data, reply) = feed(&mut parser, &[b'a', IAC]);
+        assert_eq!(data, b"a");
+        assert!(reply.is_empty());
+
+        let (data, reply) = feed(&mut parser, &[IAC, b'b', IAC, WILL]);
+        assert_eq!(data, vec![IAC, b'b']);
+        assert!(reply.is_empty());
+
+        let (_, reply) = feed(&mut parser, &[OPT_SGA]);
+        assert_eq!(reply, vec![IAC, DO, OPT_SGA]);
+
+        assert_eq!(escape_iac(&[1, IAC, 2]), vec![1, IAC, IAC, 2]);
+    }
+
+    #[test]
+    fn test_parser_com_port_option() {
+        let mut parser = TelnetParser::new("VT100".to_string(), (80, 24));
+
+        // Без запроса опция отклоняется
+        let (_, reply) = feed(&mut parser, &[IAC, DO, OPT_COM_PORT]);
+        assert_eq!(reply, vec![IAC, WONT, OPT_COM_PORT]);
+
+        assert_eq!(parser.request_com_port(9600, SerialSettings::default()), vec![IAC, WILL, OPT_COM_PORT]);
+        let (_, reply) = feed(&mut parser, &[IAC, DO, OPT_COM_PORT]);
+        let baud = [IAC, SB, OPT_COM_PORT, COM_PORT_SET_BAUDRATE, 0, 0, 0x25, 0x80, IAC, SE];
+        assert!(reply.starts_with(&baud));
+        assert_eq!(parser.com_port_state(), ComPortState::Requested);
+
+        feed(&mut parser, &[IAC, SB, OPT_COM_PORT, 101, 0, 0, 0x25, 0x80, IAC, SE]);
+        assert_eq!(parser.com_port_state(), ComPortState::Acknowledged(9600));
+    }
+
+    #[test]
+    fn test_parser_com_port_serial_settings() {
+        let mut parser = TelnetParser::new("VT100".to_string(), (80, 24));
+        let settings = SerialSettings { data_bits: DataBits::Seven, parity: Parity::Even, stop_bits: StopBits::Two };
+        parser.request_com_port(115200, settings);
+
+        let (_, reply) = feed(&mut parser, &[IAC, DO, OPT_COM_PORT]);
+        let mut expected = vec![IAC, SB, OPT_COM_PORT, COM_PORT_SET_BAUDRATE, 0, 0x01, 0xC2, 0x00, IAC, SE];
+        expected.extend_from_slice(&[IAC, SB, OPT_COM_PORT, COM_PORT_SET_DATASIZE, 7, IAC, SE]);
+        expected.extend_from_slice(&[IAC, SB, OPT_COM_PORT, COM_PORT_SET_PARITY, 3, IAC, SE]);
+        expected.extend_from_slice(&[IAC, SB, OPT_COM_PORT, COM_PORT_SET_STOPSIZE, 2, IAC, SE]);
+        assert_eq!(reply, expected);
+    }
+
+    #[test]
+    fn test_transport_against_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+            socket.write_all(&[IAC, WILL, OPT_ECHO, IAC, WILL, OPT_SGA, IAC, DO, OPT_NAWS]).unwrap();
+            socket.write_all(b"Welcome\r\nesr-200# ").unwrap();
+
+            // Собираем все, что прислал клиент, до перевода строки команды
+            let mut received = Vec::new();
+            let mut chunk = [0u8; 256];
+            while !received.ends_with(b"\r\n") {
+                let n = socket.read(&mut chunk).unwrap();
+                assert!(n > 0);
+                received.extend_from_slice(&chunk[..n]);
+            }
+
+            socket.write_all(b"show version\r\nVersion 1.0\r\nesr-200# ").unwrap();
+            received
+        });
+
+        let mut transport = TelnetTransport::new("127.0.0.1".to_string(), port);
+        transport.connect().unwrap();
+        transport.set_timeout(Duration::from_secs(5)).unwrap();
+
+        let welcome = transport.receive_until_prompt("esr-200#").unwrap();
+        assert!(welcome.contains("Welcome"));
+        assert!(!welcome.as_bytes().contains(&IAC));
+
+        transport.send_string("show version").unwrap();
+        let response = transport.receive_until_prompt("esr-200#").unwrap();
+        assert!(response.contains("Version 1.0"));
+
+        let received = server.join().unwrap();
+        let expected = [IAC, DO, OPT_ECHO, IAC, DO, OPT_SGA, IAC, WILL, OPT_NAWS];
+        assert!(received.windows(expected.len()).any(|w| w == expected));
+        assert!(received.ends_with(b"show version\r\n"));
+
+        transport.disconnect().unwrap();
+    }
+
+    #[test]
+    fn test_rfc2217_baud_rate_negotiation() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+            let mut request = [0u8; 3];
+            socket.read_exact(&mut request).unwrap();
+            assert_eq!(request, [IAC, WILL, OPT_COM_PORT]);
+            socket.write_all(&[IAC, DO, OPT_COM_PORT]).unwrap();
+
+            // Скорость (10 байт) и затем размер данных, четность, стоп-биты (по 7 байт)
+            let mut settings = [0u8; 31];
+            socket.read_exact(&mut settings).unwrap();
+            assert_eq!(&settings[10..], &[
+                IAC, SB, OPT_COM_PORT, COM_PORT_SET_DATASIZE, 8, IAC, SE,
+                IAC, SB, OPT_COM_PORT, COM_PORT_SET_PARITY, 1, IAC, SE,
+                IAC, SB, OPT_COM_PORT, COM_PORT_SET_STOPSIZE, 1, IAC, SE,
+            ]);
+            let baud = u32::from_be_bytes([settings[4], settings[5], settings[6], settings[7]]);
+
+            let mut ack = vec![IAC, SB, OPT_COM_PORT, 101];
+            ack.extend_from_slice(&baud.to_be_bytes());
+            ack.extend_from_slice(&[IAC, SE]);
+            socket.write_all(&ack).unwrap();
+            socket.write_all(b"console ready\r\n").unwrap();
+            baud
+        });
+
+        let mut transport = TelnetTransport::new("127.0.0.1".to_string(), port)
+            .with_com_port_baud_rate(9600);
+        transport.connect().unwrap();
+
+        let banner = transport.receive_until_prompt("ready").unwrap();
+        assert!(banner.contains("console ready"));
+        assert_eq!(server.join().unwrap(), 9600);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::error::types::Result;
+use super::expect::{self, ExpectMatch};
+use regex::Regex;
 use std::time::Duration;
 
 pub trait Transport {
@@ -8,10 +10,20 @@ pub trait Transport {
     fn disconnect(&mut self) -> Result<()>;
     // Send data to device
     fn send(&mut self, data: &[u8]) -> Result<()>;
-    // Get data on delimiter (Example: "\n")
-    fn receive_until(&mut self, delimiter: u8) -> Result<Vec<u8>>;
+    // Read available data, waiting no longer than `wait` (0 - no data yet)
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize>;
+    // Put data back so the next read_chunk returns it first
+    fn unread(&mut self, data: &[u8]);
     // Set timeout for device
     fn set_timeout(&mut self, timeout: Duration) -> Result<()>;
+    // Current timeout for device
+    fn timeout(&self) -> Duration;
+
+    // Get data on delimiter (Example: "\n")
+    fn receive_until(&mut self, delimiter: u8) -> Result<Vec<u8>> {
+        let timeout = self.timeout();
+        expect::receive_until(self, delimiter, timeout)
+    }
     
     fn send_string(&mut self, data: &str) -> Result<()> {
         let mut buffer = data.as_bytes().to_vec();
@@ -33,6 +45,61 @@ pub trait Transport {
         self.receive_string()
     }
 
-    // Новый метод для чтения до промпта
-    fn receive_until_prompt(&mut self, prompt: &str) -> Result<String>;
-}
\ No newline at end of file
+    /// Ожидает первое совпадение одного из шаблонов.
+    /// По истечении `timeout` возвращает `Error::Timeout` с накопленными данными
+    fn expect(&mut self, patterns: &[Regex], timeout: Duration) -> Result<ExpectMatch> {
+        expect::expect(self, patterns, timeout)
+    }
+
+    /// Читает до промпта (точное совпадение текста, включая уже полученные
+    /// пробелы после него) с таймаутом транспорта
+    fn receive_until_prompt(&mut self, prompt: &str) -> Result<String> {
+        let pattern = Regex::new(&format!("{}[ \\t]*", regex::escape(prompt)))
+            .map_err(|e| crate::error::types::Error::ConfigValidation(e.to_string()))?;
+        let timeout = self.timeout();
+        Ok(self.expect(&[pattern], timeout)?.text())
+    }
+}
+
+// Позволяет передавать транспорт, выбранный в рантайме, в generic-драйверы устройств
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn connect(&mut self) -> Result<()> {
+        (**self).connect()
+    }
+
+    fn disconnect(&mut self) -> Result<()> {
+        (**self).disconnect()
+    }
+
+    fn send(&mut self, data: &[u8]) -> Result<()> {
+        (**self).send(data)
+    }
+
+    fn read_chunk(&mut self, buf: &mut [u8], wait: Duration) -> Result<usize> {
+        (**self).read_chunk(buf, wait)
+    }
+
+    fn unread(&mut self, data: &[u8]) {
+        (**self).unread(data)
+    }
+
+    fn set_timeout(&mut self, timeout: Duration) -> Result<()> {
+        (**self).set_timeout(timeout)
+    }
+
+    fn timeout(&self) -> Duration {
+        (**self).timeout()
+    }
+
+    fn receive_until(&mut self, delimiter: u8) -> Result<Vec<u8>> {
+        (**self).receive_until(delimiter)
+    }
+
+    fn expect(&mut self, patterns: &[Regex], timeout: Duration) -> Result<ExpectMatch> {
+        (**self).expect(patterns, timeout)
+    }
+
+    fn receive_until_prompt(&mut self, prompt: &str) -> Result<String> {
+        (**self).receive_until_prompt(prompt)
+    }
+}